@@ -235,3 +235,36 @@ impl From<u64> for GasCost {
         GasCost(cost)
     }
 }
+
+/// Hard forks whose gas refund rules are supported by the circuits. Only the
+/// refund rules follow the fork: the gas charged is the EIP-2929 schedule,
+/// which Berlin introduced and London kept, so earlier forks are not listed.
+/// Refund rules are unchanged after London, so it is the latest entry.
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum HardFork {
+    /// EIP-2929 warm/cold storage access, with the EIP-2200 refunds.
+    Berlin,
+    /// EIP-3529 reduction in refunds.
+    #[default]
+    London,
+}
+
+impl HardFork {
+    /// Refund for clearing a storage slot.
+    pub const fn sstore_clears_schedule(&self) -> GasCost {
+        match self {
+            Self::Berlin => GasCost(15000),
+            Self::London => GasCost::SSTORE_CLEARS_SCHEDULE,
+        }
+    }
+
+    /// Quotient for max refund of gas used.
+    pub const fn max_refund_quotient(&self) -> u64 {
+        match self {
+            Self::Berlin => 2,
+            Self::London => MAX_REFUND_QUOTIENT_OF_GAS_USED as u64,
+        }
+    }
+}
@@ -984,6 +984,30 @@ impl<F: Field> BytecodeCircuitConfig<F> {
     }
 }
 
+/// Error returned when the bytecodes of a block don't fit in the circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BytecodeCircuitError {
+    /// The bytecodes take `used` rows but only `max` are available
+    CapacityExceeded {
+        /// Rows taken by the bytecodes
+        used: usize,
+        /// Rows available to the bytecodes
+        max: usize,
+    },
+}
+
+impl std::fmt::Display for BytecodeCircuitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CapacityExceeded { used, max } => {
+                write!(f, "bytecodes take {used} rows but only {max} are available")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BytecodeCircuitError {}
+
 /// BytecodeCircuit
 #[derive(Clone, Default, Debug)]
 pub struct BytecodeCircuit<F: Field> {
@@ -1014,6 +1038,35 @@ impl<F: Field> BytecodeCircuit<F> {
             .collect();
         Self::new(bytecodes, bytecode_size)
     }
+
+    /// Returns the number of rows taken by the bytecodes and the number of
+    /// rows available to them.
+    pub fn rows_usage(&self) -> (usize, usize) {
+        let used = self
+            .bytecodes
+            .iter()
+            .map(|bytecode| bytecode.rows.len())
+            .sum();
+        (used, self.size.saturating_sub(Self::unusable_rows()))
+    }
+
+    /// Checks that the bytecodes fit in the circuit, otherwise the bytecodes
+    /// over capacity only fail the lookups to the bytecode table.
+    pub fn check_capacity(&self) -> Result<(), BytecodeCircuitError> {
+        let (used, max) = self.rows_usage();
+        if used > max {
+            return Err(BytecodeCircuitError::CapacityExceeded { used, max });
+        }
+        Ok(())
+    }
+
+    /// Same as `new_from_block`, but fails when the bytecodes of the block
+    /// don't fit in `max_bytecode` rows.
+    pub fn try_new_from_block(block: &witness::Block<F>) -> Result<Self, BytecodeCircuitError> {
+        let circuit = Self::new_from_block_sized(block, block.circuits_params.max_bytecode);
+        circuit.check_capacity()?;
+        Ok(circuit)
+    }
 }
 
 impl<F: Field> SubCircuit<F> for BytecodeCircuit<F> {
@@ -1033,7 +1086,11 @@ impl<F: Field> SubCircuit<F> for BytecodeCircuit<F> {
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
         let bytecode_size = block.circuits_params.max_bytecode;
-        Self::new_from_block_sized(block, bytecode_size)
+        let circuit = Self::new_from_block_sized(block, bytecode_size);
+        if let Err(err) = circuit.check_capacity() {
+            log::error!("Bytecode Circuit: {err}");
+        }
+        circuit
     }
 
     /// Return the minimum number of rows required to prove the block
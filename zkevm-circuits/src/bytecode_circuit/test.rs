@@ -1,13 +1,18 @@
 #![allow(unused_imports)]
 use crate::{
-    bytecode_circuit::{bytecode_unroller::*, circuit::BytecodeCircuit},
+    bytecode_circuit::{
+        bytecode_unroller::*,
+        circuit::{BytecodeCircuit, BytecodeCircuitError},
+    },
     table::BytecodeFieldTag,
     util::{is_push_with_data, keccak, unusable_rows, Challenges, SubCircuit},
+    witness::block_convert,
 };
-use bus_mapping::{evm::OpcodeId, state_db::CodeDB};
-use eth_types::{Bytecode, Field, ToWord, Word};
+use bus_mapping::{evm::OpcodeId, mock::BlockData, state_db::CodeDB};
+use eth_types::{geth_types::GethData, Bytecode, Field, ToWord, Word};
 use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
 use log::error;
+use mock::TestContext;
 
 #[test]
 fn bytecode_circuit_unusable_rows() {
@@ -163,6 +168,52 @@ fn bytecode_incomplete() {
     test_bytecode_circuit_unrolled::<Fr>(k, vec![unroll(vec![7u8; 2usize.pow(k) + 1])], false);
 }
 
+/// Tests that bytecodes over the circuit capacity are rejected up front
+#[test]
+fn bytecode_capacity_exceeded() {
+    let k = 9;
+    // 8 bytecodes of 65 rows each don't fit in the 506 usable rows
+    let bytecodes: Vec<_> = (0..8u8).map(|byte| unroll(vec![byte; 64])).collect();
+    let circuit = BytecodeCircuit::<Fr>::new(bytecodes, 2usize.pow(k));
+    assert_eq!(
+        circuit.check_capacity(),
+        Err(BytecodeCircuitError::CapacityExceeded {
+            used: 8 * 65,
+            max: 2usize.pow(k) - BytecodeCircuit::<Fr>::unusable_rows(),
+        })
+    );
+
+    // the last usable row can still hold a byte
+    let circuit =
+        BytecodeCircuit::<Fr>::new(vec![unroll(vec![7u8; 2usize.pow(k) - 7])], 2usize.pow(k));
+    assert_eq!(circuit.check_capacity(), Ok(()));
+}
+
+/// Tests that a block with more bytecode rows than `max_bytecode` is rejected
+#[test]
+fn bytecode_capacity_exceeded_from_block() {
+    let code = Bytecode::from(vec![OpcodeId::STOP.as_u8(); 64]);
+    let geth_data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+        .unwrap()
+        .into();
+    let mut builder = BlockData::new_from_geth_data(geth_data.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+        .unwrap();
+    let mut block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    let (used, _) = BytecodeCircuit::<Fr>::min_num_rows_block(&block);
+    block.circuits_params.max_bytecode = used;
+    assert!(matches!(
+        BytecodeCircuit::<Fr>::try_new_from_block(&block),
+        Err(BytecodeCircuitError::CapacityExceeded { used: rows, max })
+            if rows == used && max == used - BytecodeCircuit::<Fr>::unusable_rows()
+    ));
+
+    block.circuits_params.max_bytecode = used + BytecodeCircuit::<Fr>::unusable_rows();
+    assert!(BytecodeCircuit::<Fr>::try_new_from_block(&block).is_ok());
+}
+
 /// Tests multiple bytecodes in a single circuit
 #[test]
 fn bytecode_push() {
@@ -1080,6 +1080,32 @@ impl<F: Field> CopyCircuit<F> {
     }
 }
 
+#[cfg(any(feature = "test", test))]
+impl<F: Field> CopyCircuit<F> {
+    /// Returns the `(value_word_rlc, value_acc)` of every row of the copy
+    /// events, computed with all the challenges fixed to `challenge` instead
+    /// of the ones drawn by the prover. The RLCs are then reproducible across
+    /// runs, which helps to track down copy value mismatches.
+    pub fn copy_value_rlcs(&self, challenge: F) -> Vec<(F, F)> {
+        let challenge = Value::known(challenge);
+        let challenges = Challenges::mock(challenge, challenge, challenge);
+        self.copy_events
+            .iter()
+            .flat_map(|copy_event| CopyTable::assignments(copy_event, challenges))
+            .map(|(_, _, circuit_row)| {
+                let column = |label| {
+                    circuit_row
+                        .iter()
+                        .find(|(_, l)| *l == label)
+                        .and_then(|(value, _)| crate::test_util::escape_value(*value))
+                        .unwrap_or_else(|| panic!("no known {label} in copy circuit row"))
+                };
+                (column("value_word_rlc"), column("value_acc"))
+            })
+            .collect()
+    }
+}
+
 impl<F: Field> SubCircuit<F> for CopyCircuit<F> {
     type Config = CopyCircuitConfig<F>;
 
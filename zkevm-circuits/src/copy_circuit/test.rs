@@ -3,7 +3,8 @@
 use crate::{
     copy_circuit::*,
     evm_circuit::{test::rand_bytes, witness::block_convert},
-    util::unusable_rows,
+    state_circuit::StateCircuit,
+    util::{log2_ceil, unusable_rows, SubCircuit},
     witness::Block,
 };
 use bus_mapping::{
@@ -411,6 +412,46 @@ fn copy_circuit_valid_return() {
     assert_eq!(test_copy_circuit_from_block(block), Ok(()));
 }
 
+#[test]
+fn copy_circuit_valid_injected_memory_copy() {
+    let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! { STOP }).unwrap();
+    let block: GethData = test_ctx.into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    let mut block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    let num_copy_events = block.copy_events.len();
+    block.add_memory_copy_event(1, 0x40, 0x100, &rand_bytes(64));
+    assert_eq!(block.copy_events.len(), num_copy_events + 1);
+    assert_eq!(block.validate_rw_counters(), Ok(()));
+
+    // The injected memory rws must be accepted by the state circuit too.
+    let (_, rows_needed) = StateCircuit::<Fr>::min_num_rows_block(&block);
+    let k = log2_ceil(rows_needed + StateCircuit::<Fr>::unusable_rows());
+    let state_circuit = StateCircuit::<Fr>::new(block.rws.clone(), rows_needed);
+    let instance = state_circuit.instance();
+    let prover = MockProver::<Fr>::run(k, &state_circuit, instance).unwrap();
+    assert_eq!(prover.verify_par(), Ok(()));
+
+    assert_eq!(test_copy_circuit_from_block(block), Ok(()));
+}
+
+#[test]
+fn copy_circuit_fixed_challenge_rlcs() {
+    let copy_value_rlcs = |challenge: u64| {
+        let builder = gen_calldatacopy_data();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        CopyCircuit::<Fr>::new_from_block_no_external(&block).copy_value_rlcs(Fr::from(challenge))
+    };
+
+    let rlcs = copy_value_rlcs(0x100);
+    assert!(!rlcs.is_empty());
+    assert_eq!(rlcs, copy_value_rlcs(0x100));
+    assert_ne!(rlcs, copy_value_rlcs(0x101));
+}
+
 #[test]
 fn copy_circuit_invalid_calldatacopy() {
     let mut builder = gen_calldatacopy_data();
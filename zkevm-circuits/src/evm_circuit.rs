@@ -28,9 +28,10 @@ use crate::{
     util::{SubCircuit, SubCircuitConfig},
 };
 use bus_mapping::evm::OpcodeId;
-use eth_types::Field;
+use eth_types::{evm_types::HardFork, Field};
 use execution::ExecutionConfig;
 use itertools::Itertools;
+use std::{collections::BTreeSet, fmt, sync::Arc};
 use strum::IntoEnumIterator;
 use table::FixedTableTag;
 use witness::Block;
@@ -84,6 +85,8 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub ecc_table: EccTable,
     // Power of Randomness Table.
     pub pow_of_rand_table: PowOfRandTable,
+    /// Hard fork whose gas refund rules are enforced.
+    pub hard_fork: HardFork,
 }
 
 /// Circuit exported cells after synthesis, used for subcircuit
@@ -114,6 +117,7 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             modexp_table,
             ecc_table,
             pow_of_rand_table,
+            hard_fork,
         }: Self::ConfigArgs,
     ) -> Self {
         let fixed_table = [(); 4].map(|_| meta.fixed_column());
@@ -121,6 +125,7 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
         let execution = Box::new(ExecutionConfig::configure(
             meta,
             challenges,
+            hard_fork,
             &fixed_table,
             &byte_table,
             &tx_table,
@@ -223,6 +228,13 @@ pub struct EvmCircuit<F: Field> {
     /// Block
     pub block: Option<Block<F>>,
     fixed_table_tags: Vec<FixedTableTag>,
+    // Only load the fixed table tags queried by the executed gadgets, see
+    // `new_dev_minimal_fixed_table`.
+    minimal_fixed_table: bool,
+    // Number of disabled rows assigned before the first step, see `with_num_prefix_rows`.
+    num_prefix_rows: usize,
+    // See `set_progress_callback`.
+    progress_callback: Option<ProgressCallback>,
     pub(crate) exports: std::cell::RefCell<Option<EvmCircuitExports<Assigned<F>>>>,
 }
 
@@ -244,6 +256,37 @@ impl<F: Field> EvmCircuit<F> {
         }
     }
 
+    /// Return a new EvmCircuit which only loads the fixed table rows of the
+    /// tags queried by the gadgets of the execution states in `block`.
+    /// Intended for tests, where building the full fixed table dominates
+    /// the setup time.
+    pub fn new_dev_minimal_fixed_table(block: Block<F>) -> Self {
+        Self {
+            block: Some(block),
+            minimal_fixed_table: true,
+            ..Default::default()
+        }
+    }
+
+    /// Return the EvmCircuit with `num_prefix_rows` disabled rows assigned
+    /// before the first step. With a fixed `max_evm_rows` the prefix rows are
+    /// taken from the padding, otherwise they are added on top of the steps.
+    pub fn with_num_prefix_rows(self, num_prefix_rows: usize) -> Self {
+        Self {
+            num_prefix_rows,
+            ..self
+        }
+    }
+
+    /// Set a callback invoked with `(done, total)` execution steps while the
+    /// steps of the block are assigned, every `PROGRESS_STEP_INTERVAL` steps
+    /// and at the end of each assigned chunk. Chunks may be assigned in
+    /// parallel, so the callback must be `Send + Sync`; the reported `done`
+    /// counts never decrease.
+    pub fn set_progress_callback(&mut self, callback: Box<dyn Fn(usize, usize) + Send + Sync>) {
+        self.progress_callback = Some(ProgressCallback(Arc::from(callback)));
+    }
+
     /// Calculate which rows are "actually" used in the circuit
     pub fn get_active_rows(block: &Block<F>) -> (Vec<usize>, Vec<usize>) {
         let max_offset = Self::get_num_rows_required(block);
@@ -287,6 +330,27 @@ impl<F: Field> EvmCircuit<F> {
         // It must have one row for EndBlock and at least one unused one
         num_rows + 2
     }
+
+    /// Returns the distinct opcodes executed by the steps of the block.
+    /// Together with `ExecutionState::implemented()` it tells which of the
+    /// implemented opcodes a set of test blocks covers.
+    pub fn opcode_coverage(block: &Block<F>) -> BTreeSet<OpcodeId> {
+        block
+            .txs
+            .iter()
+            .flat_map(|tx| tx.steps.iter())
+            .filter_map(|step| step.opcode)
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+struct ProgressCallback(Arc<dyn Fn(usize, usize) + Send + Sync>);
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
 }
 
 const FIXED_TABLE_ROWS_NO_BITWISE: usize = 3647;
@@ -332,10 +396,30 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
     ) -> Result<(), Error> {
         let block = self.block.as_ref().unwrap();
 
-        config.load_fixed_table(layouter, self.fixed_table_tags.clone())?;
+        let fixed_table_tags = if self.minimal_fixed_table {
+            let execution_states = block
+                .txs
+                .iter()
+                .flat_map(|tx| tx.steps.iter())
+                .chain([&block.end_block_not_last, &block.end_block_last])
+                .map(|step| step.execution_state);
+            config
+                .execution
+                .instrument()
+                .fixed_table_tags(execution_states)
+        } else {
+            self.fixed_table_tags.clone()
+        };
+        config.load_fixed_table(layouter, fixed_table_tags)?;
         config.load_byte_table(layouter)?;
         config.pow_of_rand_table.assign(layouter, challenges)?;
-        let export = config.execution.assign_block(layouter, block, challenges)?;
+        let export = config.execution.assign_block(
+            layouter,
+            block,
+            self.num_prefix_rows,
+            self.progress_callback.as_ref().map(|callback| &*callback.0),
+            challenges,
+        )?;
         self.exports.borrow_mut().replace(export);
         Ok(())
     }
@@ -442,17 +526,13 @@ use crate::util::Challenges;
 #[cfg(feature = "onephase")]
 use crate::util::MockChallenges as Challenges;
 
-impl<F: Field> Circuit<F> for EvmCircuit<F> {
-    type Config = (EvmCircuitConfig<F>, Challenges);
-    type FloorPlanner = SimpleFloorPlanner;
-    #[cfg(feature = "circuit-params")]
-    type Params = ();
-
-    fn without_witnesses(&self) -> Self {
-        Self::default()
-    }
-
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+impl<F: Field> EvmCircuit<F> {
+    /// Configures the circuit like [`Circuit::configure`], enforcing the gas
+    /// refund rules of `hard_fork` instead of the latest ones.
+    pub fn configure_with_hard_fork(
+        meta: &mut ConstraintSystem<F>,
+        hard_fork: HardFork,
+    ) -> <Self as Circuit<F>>::Config {
         let challenges = Challenges::construct(meta);
         let challenges_expr = challenges.exprs(meta);
         let rw_table = RwTable::construct(meta);
@@ -485,11 +565,30 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     modexp_table,
                     ecc_table,
                     pow_of_rand_table,
+                    hard_fork,
                 },
             ),
             challenges,
         )
     }
+}
+
+impl<F: Field> Circuit<F> for EvmCircuit<F> {
+    type Config = (EvmCircuitConfig<F>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            num_prefix_rows: self.num_prefix_rows,
+            ..Default::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        Self::configure_with_hard_fork(meta, HardFork::default())
+    }
 
     fn synthesize(
         &self,
@@ -569,7 +668,7 @@ mod evm_circuit_stats {
             EvmCircuit, FIXED_TABLE_ROWS, FIXED_TABLE_ROWS_NO_BITWISE,
         },
         stats::print_circuit_stats_by_states,
-        test_util::CircuitTestBuilder,
+        test_util::{run_with_budget, BudgetError, CircuitTestBuilder},
         util::{unusable_rows, SubCircuit},
         witness::block_convert,
     };
@@ -577,7 +676,7 @@ mod evm_circuit_stats {
     use cli_table::{print_stdout, Cell, Style, Table};
     use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, ToWord};
     use halo2_proofs::{
-        dev::MockProver,
+        dev::{CellValue, MockProver},
         halo2curves::bn256::Fr,
         plonk::{Circuit, ConstraintSystem},
     };
@@ -589,6 +688,11 @@ mod evm_circuit_stats {
         },
         MOCK_ACCOUNTS,
     };
+    use std::{
+        env, fs,
+        path::Path,
+        sync::{Arc, Mutex},
+    };
     use strum::IntoEnumIterator;
 
     #[test]
@@ -623,6 +727,16 @@ mod evm_circuit_stats {
         );
     }
 
+    #[test]
+    fn implemented_execution_states() {
+        let implemented = ExecutionState::implemented();
+        assert!(implemented.contains(&ExecutionState::CHAINID));
+        assert!(implemented.contains(&ExecutionState::ErrorInvalidJump));
+        assert!(implemented
+            .iter()
+            .all(|state| state.get_step_height_option().is_some()));
+    }
+
     #[test]
     fn evm_circuit_unusable_rows() {
         assert_eq!(
@@ -650,6 +764,41 @@ mod evm_circuit_stats {
         .run();
     }
 
+    #[test]
+    pub fn empty_evm_circuit_with_minimal_padding() {
+        // The last EndBlock, its unused next row and a single padding EndBlock.
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<0, 0>::new(None, |_| {}, |_, _| {}, |b, _| b).unwrap(),
+        )
+        .block_modifier(Box::new(|block| block.circuits_params.max_evm_rows = 3))
+        .run();
+    }
+
+    #[test]
+    fn empty_evm_circuit_min_num_rows() {
+        let block: GethData = TestContext::<0, 0>::new(None, |_| {}, |_, _| {}, |b, _| b)
+            .unwrap()
+            .into();
+        let mut builder =
+            BlockData::new_from_geth_data_with_params(block.clone(), CircuitsParams::default())
+                .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        assert!(block.txs.is_empty());
+
+        // Only the EndBlock step and the unused row after it.
+        assert_eq!(EvmCircuit::<Fr>::get_min_num_rows_required(&block), 2);
+        assert_eq!(
+            EvmCircuit::<Fr>::get_num_rows_required_no_padding(&block),
+            2
+        );
+        let (num_rows_required, total_rows) = EvmCircuit::<Fr>::min_num_rows_block(&block);
+        assert_eq!(num_rows_required, 2);
+        assert!(total_rows >= FIXED_TABLE_ROWS_NO_BITWISE);
+    }
+
     /// Prints the stats of EVM circuit per execution state.  See
     /// `print_circuit_stats_by_states` for more details.
     ///
@@ -776,6 +925,258 @@ mod evm_circuit_stats {
         );
     }
 
+    #[test]
+    fn evm_circuit_with_prefix_rows() {
+        let num_prefix_rows = 8;
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |b, _| b,
+        )
+        .unwrap()
+        .into();
+        let mut builder =
+            BlockData::new_from_geth_data_with_params(block.clone(), CircuitsParams::default())
+                .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        let k = block.get_evm_test_circuit_degree();
+
+        let circuit = EvmCircuit::<Fr>::get_test_cicuit_from_block(block)
+            .with_num_prefix_rows(num_prefix_rows);
+        let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+
+        // `q_usable` is only enabled from the first step on.
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let (config, _) = EvmCircuit::<Fr>::configure(&mut meta);
+        let q_usable = &prover.fixed()[config.execution.q_usable.index()];
+        let first_step_offset = q_usable
+            .iter()
+            .position(|value| *value == CellValue::Assigned(Fr::from(1)))
+            .unwrap();
+        assert_eq!(first_step_offset, num_prefix_rows);
+    }
+
+    #[test]
+    fn evm_circuit_progress_callback() {
+        let mut code = bytecode! {};
+        for _ in 0..100 {
+            code.push(1, 0x01);
+            code.op_pop();
+        }
+        code.op_stop();
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |b, _| b,
+        )
+        .unwrap()
+        .into();
+        let mut builder =
+            BlockData::new_from_geth_data_with_params(block.clone(), CircuitsParams::default())
+                .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        let k = block.get_evm_test_circuit_degree();
+        let total_step_num = block.txs.iter().map(|tx| tx.steps.len()).sum::<usize>();
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut circuit = EvmCircuit::<Fr>::get_test_cicuit_from_block(block);
+        circuit.set_progress_callback(Box::new({
+            let calls = calls.clone();
+            move |done, total| calls.lock().unwrap().push((done, total))
+        }));
+        let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+
+        let calls = calls.lock().unwrap();
+        assert!(!calls.is_empty());
+        assert!(calls.iter().all(|(_, total)| *total == total_step_num));
+        assert!(calls.iter().tuple_windows().all(|(a, b)| a.0 <= b.0));
+        assert_eq!(calls.last().unwrap().0, total_step_num);
+    }
+
+    #[test]
+    fn evm_circuit_opcode_coverage() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            POP
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |b, _| b,
+        )
+        .unwrap()
+        .into();
+        let mut builder =
+            BlockData::new_from_geth_data_with_params(block.clone(), CircuitsParams::default())
+                .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+        let coverage = EvmCircuit::<Fr>::opcode_coverage(&block);
+        assert_eq!(
+            coverage.into_iter().collect_vec(),
+            vec![
+                OpcodeId::STOP,
+                OpcodeId::ADD,
+                OpcodeId::POP,
+                OpcodeId::PUSH1
+            ]
+        );
+    }
+
+    #[test]
+    fn evm_circuit_run_with_budget() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            POP
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |b, _| b,
+        )
+        .unwrap()
+        .into();
+        let mut builder =
+            BlockData::new_from_geth_data_with_params(block.clone(), CircuitsParams::default())
+                .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+        // Without padding, the steps, a single padding EndBlock and the last
+        // EndBlock are the `q_usable` rows. With the unused next row of the
+        // last EndBlock, they are the least rows the steps fit in.
+        assert_eq!(block.circuits_params.max_evm_rows, 0);
+        let circuit = EvmCircuit::<Fr>::get_test_cicuit_from_block(block.clone());
+        let prover =
+            MockProver::<Fr>::run(block.get_evm_test_circuit_degree(), &circuit, vec![]).unwrap();
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let (config, _) = EvmCircuit::<Fr>::configure(&mut meta);
+        let usable_rows = prover.fixed()[config.execution.q_usable.index()]
+            .iter()
+            .filter(|value| **value == CellValue::Assigned(Fr::from(1)))
+            .count();
+        let min_rows = usable_rows + 1;
+
+        // Bisect the minimal budget the block fits in.
+        let (mut lo, mut hi) = (1, 1 << 12);
+        assert!(run_with_budget(block.clone(), hi).is_ok());
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            match run_with_budget(block.clone(), mid) {
+                Ok(_) => hi = mid,
+                Err(BudgetError::ExceedsBudget { .. }) => lo = mid + 1,
+                Err(err) => panic!("{err}"),
+            }
+        }
+        assert_eq!(lo, min_rows);
+
+        let mut padded_block = block.clone();
+        padded_block.circuits_params.max_evm_rows = lo;
+        let (active_gate_rows, active_lookup_rows) =
+            EvmCircuit::<Fr>::get_active_rows(&padded_block);
+        let prover = run_with_budget(block.clone(), lo).unwrap();
+        assert_eq!(
+            prover.verify_at_rows_par(active_gate_rows.into_iter(), active_lookup_rows.into_iter()),
+            Ok(())
+        );
+        match run_with_budget(block, lo - 1) {
+            Err(BudgetError::ExceedsBudget {
+                rows_needed,
+                max_rows,
+            }) => assert_eq!((rows_needed, max_rows), (min_rows, min_rows - 1)),
+            _ => panic!("expected the block to exceed a budget of {} rows", lo - 1),
+        }
+    }
+
+    /// Renders the name and degree of every constraint in the gates named
+    /// `gate_name`, one per line.
+    fn gate_shape(meta: &ConstraintSystem<Fr>, gate_name: &str) -> String {
+        meta.gates()
+            .iter()
+            .filter(|gate| gate.name() == gate_name)
+            .flat_map(|gate| {
+                gate.polynomials()
+                    .iter()
+                    .enumerate()
+                    .map(move |(idx, poly)| {
+                        format!("{}: degree {}\n", gate.constraint_name(idx), poly.degree())
+                    })
+            })
+            .collect()
+    }
+
+    /// Compares the gates configured for `gate_name` against the committed
+    /// `tests/snapshots/{gate_name}.snap`.
+    /// Set `UPDATE_SNAPSHOTS` to (re)write the snapshot after an intended
+    /// constraint change.
+    fn assert_gate_snapshot(gate_name: &str) {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        EvmCircuit::<Fr>::configure(&mut meta);
+        let shape = gate_shape(&meta, gate_name);
+        assert!(!shape.is_empty(), "no gate named {gate_name}");
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/snapshots")
+            .join(format!("{gate_name}.snap"));
+        if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, shape).unwrap();
+            return;
+        }
+        let snapshot = fs::read_to_string(&path).unwrap_or_else(|err| {
+            panic!(
+                "can't read snapshot {}: {err}, run with UPDATE_SNAPSHOTS=1 to create it",
+                path.display()
+            )
+        });
+        assert_eq!(
+            shape, snapshot,
+            "constraints of {gate_name} changed, rerun with UPDATE_SNAPSHOTS=1 if intended"
+        );
+    }
+
+    // The constraint names in the snapshots carry the gadget annotation.
+    #[cfg(feature = "debug-annotations")]
+    #[test]
+    fn chainid_gate_snapshot() {
+        assert_gate_snapshot("CHAINID");
+    }
+
+    #[cfg(feature = "debug-annotations")]
+    #[test]
+    fn error_invalid_jump_gate_snapshot() {
+        assert_gate_snapshot("ErrorInvalidJump");
+    }
+
     #[ignore = "need to make table dev_load padding to fix this"]
     #[test]
     fn variadic_size_check() {
@@ -25,7 +25,7 @@ use crate::{
     util::{query_expression, Challenges, Expr},
 };
 use bus_mapping::util::read_env_var;
-use eth_types::{Field, ToLittleEndian};
+use eth_types::{evm_types::HardFork, Field, ToLittleEndian};
 use gadgets::util::not;
 use halo2_proofs::{
     circuit::{Layouter, Region, Value},
@@ -39,7 +39,12 @@ use itertools::Itertools;
 use std::{
     collections::{BTreeSet, HashMap},
     iter,
-    sync::LazyLock,
+    sync::{LazyLock, Mutex},
+};
+#[cfg(any(feature = "test", test))]
+use {
+    super::{table::LookupDescriptor, EvmCircuit},
+    halo2_proofs::plonk::Circuit,
 };
 
 #[cfg(feature = "onephase")]
@@ -55,6 +60,10 @@ use strum::{EnumCount, IntoEnumIterator};
 pub(crate) static CHECK_RW_LOOKUP: LazyLock<bool> =
     LazyLock::new(|| read_env_var("CHECK_RW_LOOKUP", false));
 
+/// Number of assigned steps between two calls of the progress callback, see
+/// `EvmCircuit::set_progress_callback`.
+pub(crate) const PROGRESS_STEP_INTERVAL: usize = 1000;
+
 mod add_sub;
 mod addmod;
 mod address;
@@ -241,6 +250,22 @@ pub(crate) trait ExecutionGadget<F: Field> {
         call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error>;
+
+    /// Returns the lookups the gadget adds in `configure`, as recorded when
+    /// configuring the EVM circuit.
+    #[cfg(any(feature = "test", test))]
+    fn lookups() -> Vec<LookupDescriptor>
+    where
+        Self: Sized,
+    {
+        let mut meta = ConstraintSystem::<F>::default();
+        let (config, _) = EvmCircuit::<F>::configure(&mut meta);
+        config
+            .execution
+            .instrument()
+            .lookups(Self::EXECUTION_STATE)
+            .to_vec()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -248,7 +273,7 @@ pub(crate) struct ExecutionConfig<F> {
     // EVM Circuit selector, which enables all usable rows.  The rows where this selector is
     // disabled won't verify any constraint (they can be unused rows or rows with blinding
     // factors).
-    q_usable: Column<Fixed>,
+    pub(crate) q_usable: Column<Fixed>,
     // Dynamic selector that is enabled at the rows where each assigned execution step starts (a
     // step has dynamic height).
     q_step: Column<Advice>,
@@ -374,6 +399,7 @@ impl<F: Field> ExecutionConfig<F> {
     pub(crate) fn configure(
         meta: &mut ConstraintSystem<F>,
         challenges: Challenges<Expression<F>>,
+        hard_fork: HardFork,
         fixed_table: &dyn LookupTable<F>,
         byte_table: &dyn LookupTable<F>,
         tx_table: &dyn LookupTable<F>,
@@ -526,6 +552,7 @@ impl<F: Field> ExecutionConfig<F> {
                         q_step_first,
                         q_step_last,
                         &challenges,
+                        hard_fork,
                         &step_curr,
                         &mut height_map,
                         &mut stored_expressions_map,
@@ -689,6 +716,7 @@ impl<F: Field> ExecutionConfig<F> {
         q_step_first: Selector,
         q_step_last: Selector,
         challenges: &Challenges<Expression<F>>,
+        hard_fork: HardFork,
         step_curr: &Step<F>,
         height_map: &mut HashMap<ExecutionState, usize>,
         stored_expressions_map: &mut HashMap<ExecutionState, Vec<StoredExpression<F>>>,
@@ -702,6 +730,7 @@ impl<F: Field> ExecutionConfig<F> {
                 step_curr.clone(),
                 dummy_step_next,
                 challenges,
+                hard_fork,
                 G::EXECUTION_STATE,
             );
             cb.annotation(G::NAME, |cb| G::configure(cb));
@@ -715,6 +744,7 @@ impl<F: Field> ExecutionConfig<F> {
             step_curr.clone(),
             step_next.clone(),
             challenges,
+            hard_fork,
             G::EXECUTION_STATE,
         );
 
@@ -1051,6 +1081,8 @@ impl<F: Field> ExecutionConfig<F> {
         &self,
         layouter: &mut impl Layouter<F>,
         block: &Block<F>,
+        num_prefix_rows: usize,
+        progress_callback: Option<&(dyn Fn(usize, usize) + Send + Sync)>,
         challenges: &Challenges<Value<F>>,
     ) -> Result<EvmCircuitExports<Assigned<F>>, Error> {
         // If the height is not 1, padding to fixed height will be impossible
@@ -1061,7 +1093,7 @@ impl<F: Field> ExecutionConfig<F> {
         // 0 means "dynamic height". If fixed height is used in unittests, CI will be quite slow.
         let no_padding = evm_rows == 0;
 
-        // There should be 3 group of regions
+        // There should be 3 group of regions, after `num_prefix_rows` disabled rows
         // 1. real steps
         // 2. padding EndBlocks. For the ease of implementation, even for `no_padding` case, we will
         //    still pad 1 end_block_not_last.
@@ -1071,16 +1103,17 @@ impl<F: Field> ExecutionConfig<F> {
         let region2_height = if no_padding {
             1
         } else {
-            if region1_height + region3_height >= evm_rows {
+            if num_prefix_rows + region1_height + region3_height >= evm_rows {
                 log::error!(
-                    "evm circuit row not enough, region1_height:{}, region3_height:{}, max_evm_rows:{}",
+                    "evm circuit row not enough, num_prefix_rows:{}, region1_height:{}, region3_height:{}, max_evm_rows:{}",
+                    num_prefix_rows,
                     region1_height,
                     region3_height,
                     evm_rows
                 );
                 return Err(Error::Synthesis);
             }
-            evm_rows - region3_height - region1_height
+            evm_rows - region3_height - region1_height - num_prefix_rows
         };
 
         // A quick path for "reporting" height for the halo2 first pass layouter.
@@ -1167,6 +1200,18 @@ impl<F: Field> ExecutionConfig<F> {
             }
         };
 
+        // Report `num_steps` more assigned steps to the progress callback, if any.
+        // The counter is updated and reported under the lock so that the reported
+        // counts never decrease, even when chunks are assigned in parallel.
+        let progress_done = Mutex::new(0);
+        let report_progress_fn = |num_steps: usize| {
+            if let Some(callback) = progress_callback {
+                let mut done = progress_done.lock().unwrap();
+                *done += num_steps;
+                callback(*done, total_step_num);
+            }
+        };
+
         // Calculate chunk_size and chunk_num
         // Here a min_chunk_size is provided to reduce threading overhead
         let chunking_fn = |name: &str, task_len: usize, min_chunk_size: usize| -> (usize, usize) {
@@ -1190,6 +1235,36 @@ impl<F: Field> ExecutionConfig<F> {
             (chunk_size, chunk_num)
         };
 
+        // Step0: assign the prefix rows. `q_usable` is left disabled there, so no step
+        // constraint or lookup applies until the first step, which is placed right after them.
+        if num_prefix_rows > 0 {
+            let mut prefix_is_first_time = true;
+            layouter.assign_region(
+                || "Execution step prefix",
+                |mut region| {
+                    if prefix_is_first_time {
+                        prefix_is_first_time = false;
+                        return assign_shape_fn(&mut region, num_prefix_rows);
+                    }
+                    for offset in 0..num_prefix_rows {
+                        for (annotation, column) in [
+                            ("step selector", self.q_step),
+                            ("step height", self.num_rows_until_next_step),
+                            ("step height inv", self.num_rows_inv),
+                        ] {
+                            region.assign_advice(
+                                || annotation,
+                                column,
+                                offset,
+                                || Value::known(F::zero()),
+                            )?;
+                        }
+                    }
+                    Ok(num_prefix_rows)
+                },
+            )?;
+        }
+
         // Step1: assign real steps
         let (region1_chunk_size, region1_chunk_num) =
             chunking_fn("region1", step_assignments.len(), 50);
@@ -1218,6 +1293,7 @@ impl<F: Field> ExecutionConfig<F> {
                                 return assign_shape_fn(&mut region, total_height);
                             }
                             let mut offset = 0;
+                            let mut num_unreported_steps = 0;
 
                             // Annotate the EVMCircuit columns within it's single region.
                             self.annotate_circuit(&mut region);
@@ -1261,6 +1337,15 @@ impl<F: Field> ExecutionConfig<F> {
                                 self.assign_q_step(&mut region, &inverter, offset, height)?;
 
                                 offset += height;
+
+                                num_unreported_steps += 1;
+                                if num_unreported_steps == PROGRESS_STEP_INTERVAL {
+                                    report_progress_fn(num_unreported_steps);
+                                    num_unreported_steps = 0;
+                                }
+                            }
+                            if num_unreported_steps > 0 {
+                                report_progress_fn(num_unreported_steps);
                             }
                             debug_assert_eq!(offset, total_height);
                             Ok(total_height)
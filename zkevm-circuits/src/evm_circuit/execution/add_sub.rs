@@ -104,10 +104,17 @@ impl<F: Field> ExecutionGadget<F> for AddSubGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, evm_types::OpcodeId, Word};
-
-    use mock::TestContext;
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_word},
+        test_util::{assert_gadget_rejects, CircuitTestBuilder},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{GasCost, OpcodeId},
+        Word,
+    };
+
+    use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
 
     fn test_ok(opcode: OpcodeId, a: Word, b: Word) {
         let bytecode = bytecode! {
@@ -136,4 +143,65 @@ mod test {
         test_ok(OpcodeId::ADD, a, b);
         test_ok(OpcodeId::SUB, a, b);
     }
+
+    // A tx with exactly the gas to run ADD, so that ADD spends its last gas.
+    fn exact_gas_ctx() -> TestContext<2, 1> {
+        let bytecode = bytecode! {
+            PUSH32(0x030201)
+            PUSH32(0x060504)
+            ADD
+            STOP
+        };
+        let gas = GasCost::TX.as_u64()
+            + 2 * OpcodeId::PUSH32.constant_gas_cost().as_u64()
+            + OpcodeId::ADD.constant_gas_cost().as_u64();
+        TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas(gas.into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn add_gadget_exact_gas() {
+        // ADD leaves a next gas_left of exactly 0, the lower bound of the
+        // range check in `SameContextGadget`.
+        CircuitTestBuilder::new_from_test_ctx(exact_gas_ctx())
+            .block_modifier(Box::new(|block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::ADD_SUB)
+                    .unwrap();
+                assert_eq!(step.gas_left, step.gas_cost);
+            }))
+            .run()
+    }
+
+    #[test]
+    fn add_gadget_gas_cost_above_gas_left() {
+        // Take one gas away from the tx and from every step up to ADD, which
+        // then costs one more than its gas_left.
+        assert_gadget_rejects(
+            exact_gas_ctx(),
+            Box::new(|block| {
+                let tx = &mut block.txs[0];
+                tx.gas -= 1;
+                for step in tx.steps.iter_mut() {
+                    step.gas_left -= 1;
+                    if step.execution_state == ExecutionState::ADD_SUB {
+                        assert_eq!(step.gas_left + 1, step.gas_cost);
+                        break;
+                    }
+                }
+            }),
+        );
+    }
 }
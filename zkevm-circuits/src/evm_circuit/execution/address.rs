@@ -89,9 +89,18 @@ impl<F: Field> ExecutionGadget<F> for AddressGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, Word};
-    use mock::{generate_mock_call_bytecode, test_ctx::TestContext, MockCallBytecodeParams};
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_bytes},
+        test_util::{subcall_ctx, CircuitTestBuilder},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{GasCost, OpcodeId},
+        ToWord, Word,
+    };
+    use mock::{
+        generate_mock_call_bytecode, test_ctx::TestContext, MockCallBytecodeParams, MOCK_ACCOUNTS,
+    };
 
     fn test_root_ok() {
         let bytecode = bytecode! {
@@ -143,6 +152,39 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    fn test_address_in_subcall(opcode: OpcodeId) {
+        let ctx = subcall_ctx(
+            opcode,
+            Word::zero(),
+            Word::zero(),
+            bytecode! {
+                ADDRESS
+                STOP
+            },
+        );
+
+        // A DELEGATECALL frame runs the code of B in the storage context of A.
+        let expected = if opcode == OpcodeId::DELEGATECALL {
+            MOCK_ACCOUNTS[0]
+        } else {
+            MOCK_ACCOUNTS[1]
+        };
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::ADDRESS)
+                    .unwrap();
+                assert_eq!(step.gas_cost, GasCost::QUICK.as_u64());
+                assert_eq!(
+                    block.rws[step.rw_indices[1]].stack_value(),
+                    expected.to_word()
+                );
+            }))
+            .run();
+    }
+
     #[test]
     fn address_gadget_root() {
         test_root_ok();
@@ -155,4 +197,14 @@ mod test {
         test_internal_ok(0x40, 0x20);
         test_internal_ok(0x1010, 0xff);
     }
+
+    #[test]
+    fn address_gadget_call() {
+        test_address_in_subcall(OpcodeId::CALL);
+    }
+
+    #[test]
+    fn address_gadget_delegatecall() {
+        test_address_in_subcall(OpcodeId::DELEGATECALL);
+    }
 }
@@ -4,21 +4,21 @@ use crate::{
         param::N_BYTES_ACCOUNT_ADDRESS,
         step::ExecutionState,
         util::{
-            common_gadget::SameContextGadget,
+            common_gadget::{AccessListGadget, SameContextGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::Delta,
             },
             from_bytes,
             math_gadget::IsZeroGadget,
-            not, select, CachedRegion, Cell, Word,
+            not, CachedRegion, Cell, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
     table::{AccountFieldTag, CallContextFieldTag},
     util::Expr,
 };
-use eth_types::{evm_types::GasCost, Field, ToLittleEndian};
+use eth_types::{Field, ToLittleEndian};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
@@ -27,7 +27,7 @@ pub(crate) struct BalanceGadget<F> {
     address_word: Word<F>,
     reversion_info: ReversionInfo<F>,
     tx_id: Cell<F>,
-    is_warm: Cell<F>,
+    access_list: AccessListGadget<F>,
     code_hash: Cell<F>,
     not_exists: IsZeroGadget<F>,
     balance: Cell<F>,
@@ -45,13 +45,12 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
 
         let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
         let mut reversion_info = cb.reversion_info_read(None);
-        let is_warm = cb.query_bool();
-        cb.account_access_list_write(
+        let access_list = AccessListGadget::construct(
+            cb,
             tx_id.expr(),
             address.expr(),
-            1.expr(),
-            is_warm.expr(),
-            Some(&mut reversion_info),
+            None,
+            &mut reversion_info,
         );
         let code_hash = cb.query_cell_phase2();
         // For non-existing accounts the code_hash must be 0 in the rw_table.
@@ -68,17 +67,11 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
 
         cb.stack_push(balance.expr());
 
-        let gas_cost = select::expr(
-            is_warm.expr(),
-            GasCost::WARM_ACCESS.expr(),
-            GasCost::COLD_ACCOUNT_ACCESS.expr(),
-        );
-
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(7.expr() + exists.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(0.expr()),
-            gas_left: Delta(-gas_cost),
+            gas_left: Delta(-access_list.gas_cost()),
             reversible_write_counter: Delta(1.expr()),
             ..Default::default()
         };
@@ -91,7 +84,7 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
             address_word,
             reversion_info,
             tx_id,
-            is_warm,
+            access_list,
             code_hash,
             not_exists,
             balance,
@@ -124,8 +117,7 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
         )?;
 
         let (_, is_warm) = block.rws[step.rw_indices[4]].tx_access_list_value_pair();
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(is_warm)))?;
+        self.access_list.assign(region, offset, is_warm)?;
 
         let code_hash = block.rws[step.rw_indices[5]].account_value_pair().0;
         self.code_hash
@@ -146,9 +138,17 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{address, bytecode, geth_types::Account, Address, Bytecode, Word, U256};
-    use mock::{generate_mock_call_bytecode, test_ctx::TestContext, MockCallBytecodeParams};
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_bytes},
+        test_util::{assert_gadget_rejects, CircuitTestBuilder},
+        witness::Rw,
+    };
+    use eth_types::{
+        address, bytecode, evm_types::GasCost, geth_types::Account, Address, Bytecode, Word, U256,
+    };
+    use mock::{
+        generate_mock_call_bytecode, test_ctx::TestContext, MockCallBytecodeParams, MOCK_COINBASE,
+    };
     use std::sync::LazyLock;
 
     static TEST_ADDRESS: LazyLock<Address> =
@@ -201,6 +201,145 @@ mod test {
         test_internal_ok(0x1010, 0xff, &account, true);
     }
 
+    #[test]
+    fn balance_gadget_cold_then_warm() {
+        let code = bytecode! {
+            .op_balance(*TEST_ADDRESS)
+            POP
+            .op_balance(*TEST_ADDRESS)
+            STOP
+        };
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(Word::from(1_u64 << 20))
+                    .code(code);
+                accs[1].address(*TEST_ADDRESS).balance(Word::from(900));
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000000020"))
+                    .balance(Word::from(1_u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[2].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                let steps = block.txs[0]
+                    .steps
+                    .iter()
+                    .filter(|step| step.execution_state == ExecutionState::BALANCE)
+                    .collect::<Vec<_>>();
+                assert_eq!(steps.len(), 2);
+                for (step, was_warm) in steps.into_iter().zip([false, true]) {
+                    let (is_warm, is_warm_prev) =
+                        block.rws[step.rw_indices[4]].tx_access_list_value_pair();
+                    assert!(is_warm);
+                    assert_eq!(is_warm_prev, was_warm);
+                    let gas_cost = if was_warm {
+                        GasCost::WARM_ACCESS
+                    } else {
+                        GasCost::COLD_ACCOUNT_ACCESS
+                    };
+                    assert_eq!(step.gas_cost, gas_cost.as_u64());
+                }
+            }))
+            .run();
+    }
+
+    #[test]
+    fn balance_gadget_non_existing_account_is_cold_and_zero() {
+        let ctx = || {
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! {
+                .op_balance(*TEST_ADDRESS)
+                STOP
+            })
+            .unwrap()
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(ctx())
+            .block_modifier(Box::new(|block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::BALANCE)
+                    .unwrap();
+                let balance = block.rws[*step.rw_indices.last().unwrap()].stack_value();
+                assert_eq!(balance, Word::zero());
+                assert_eq!(step.gas_cost, GasCost::COLD_ACCOUNT_ACCESS.as_u64());
+            }))
+            .run();
+
+        // A nonexistent account must push a zero balance.
+        assert_gadget_rejects(
+            ctx(),
+            Box::new(|block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::BALANCE)
+                    .unwrap();
+                let (tag, idx) = *step.rw_indices.last().unwrap();
+                if let Rw::Stack { value, .. } = &mut block.rws.0.get_mut(&tag).unwrap()[idx] {
+                    *value = Word::one();
+                }
+            }),
+        );
+    }
+
+    // EIP-3651: the coinbase is warm from the start of the tx.
+    #[cfg(feature = "shanghai")]
+    #[test]
+    fn balance_gadget_coinbase_is_warm() {
+        let code = bytecode! {
+            .op_balance(*MOCK_COINBASE)
+            POP
+            .op_balance(*TEST_ADDRESS)
+            STOP
+        };
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(Word::from(1_u64 << 20))
+                    .code(code);
+                accs[1].address(*TEST_ADDRESS).balance(Word::from(900));
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000000020"))
+                    .balance(Word::from(1_u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[2].address);
+            },
+            |block, _tx| block.author(*MOCK_COINBASE),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                let gas_costs = block.txs[0]
+                    .steps
+                    .iter()
+                    .filter(|step| step.execution_state == ExecutionState::BALANCE)
+                    .map(|step| step.gas_cost)
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    gas_costs,
+                    [
+                        GasCost::WARM_ACCESS.as_u64(),
+                        GasCost::COLD_ACCOUNT_ACCESS.as_u64()
+                    ]
+                );
+            }))
+            .run();
+    }
+
     fn test_root_ok(account: &Option<Account>, is_warm: bool) {
         let address = account.as_ref().map(|a| a.address).unwrap_or(*TEST_ADDRESS);
 
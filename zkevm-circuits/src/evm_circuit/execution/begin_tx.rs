@@ -15,8 +15,8 @@ use crate::{
             },
             from_bytes,
             math_gadget::{
-                ConstantDivisionGadget, ContractCreateGadget, IsEqualGadget, IsZeroGadget,
-                LtGadget, MulWordByU64Gadget, RangeCheckGadget,
+                ConstantDivmodGadget, ContractCreateGadget, IsEqualGadget, IsZeroGadget, LtGadget,
+                MulWordByU64Gadget, RangeCheckGadget,
             },
             precompile_gadget::PrecompileGadget,
             CachedRegion, Cell, StepRws, Word,
@@ -54,7 +54,7 @@ pub(crate) struct BeginTxGadget<F> {
     tx_nonce: Cell<F>,
     tx_gas: Cell<F>,
     tx_gas_price: Word<F>,
-    mul_gas_fee_by_gas: MulWordByU64Gadget<F>,
+    mul_gas_fee_by_gas: MulWordByU64Gadget<F, true>,
     tx_fee: Word<F>,
     tx_caller_address: Cell<F>,
     tx_caller_address_is_zero: IsZeroGadget<F>,
@@ -65,7 +65,7 @@ pub(crate) struct BeginTxGadget<F> {
     tx_value: Word<F>,
     tx_call_data_length: Cell<F>,
     is_call_data_empty: IsZeroGadget<F>,
-    tx_call_data_word_length: ConstantDivisionGadget<F, N_BYTES_U64>,
+    tx_call_data_word_length: ConstantDivmodGadget<F, N_BYTES_U64, 32>,
     tx_call_data_gas_cost: Cell<F>,
     // The gas cost for rlp-encoded bytes of unsigned tx
     tx_data_gas_cost: Cell<F>,
@@ -246,7 +246,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let precompile_input_len = cb.query_cell();
 
         let tx_call_data_word_length =
-            ConstantDivisionGadget::construct(cb, tx_call_data_length.expr() + 31.expr(), 32);
+            ConstantDivmodGadget::construct(cb, tx_call_data_length.expr() + 31.expr());
 
         // Use intrinsic gas
         // TODO2: contrain calling precompile directly
@@ -1094,8 +1094,13 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         )?;
         self.intrinsic_gas_cost
             .assign(region, offset, Value::known(F::from(step.gas_cost)))?;
-        self.sufficient_gas_left
-            .assign(region, offset, F::from(tx.gas - step.gas_cost))?;
+        // Computed in the field, so that a gas limit below the intrinsic gas is
+        // assigned as a negative gas_left and rejected by the range check.
+        self.sufficient_gas_left.assign(
+            region,
+            offset,
+            F::from(tx.gas) - F::from(step.gas_cost),
+        )?;
         self.call_code_hash
             .assign(region, offset, region.code_hash(call.code_hash))?;
         let untrimmed_contract_addr = {
@@ -1248,7 +1253,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
 mod test {
     use std::{str::FromStr, vec};
 
-    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
+    use crate::{
+        evm_circuit::test::rand_bytes,
+        test_util::{assert_gadget_rejects, CircuitTestBuilder},
+    };
     use bus_mapping::evm::OpcodeId;
     use eth_types::{
         self, address, bytecode, evm_types::GasCost, word, Address, Bytecode, Hash, Word, U256,
@@ -1344,6 +1352,82 @@ mod test {
         );
     }
 
+    fn intrinsic_gas(call_data: &[u8]) -> u64 {
+        GasCost::TX.as_u64()
+            + call_data
+                .iter()
+                .map(|&x| if x == 0 { 4 } else { 16 })
+                .sum::<u64>()
+    }
+
+    // Sends `call_data` to an account without code, with a gas limit of
+    // exactly the intrinsic gas, so that no gas is left after BeginTx.
+    fn exact_intrinsic_gas_ctx(call_data: Vec<u8>) -> TestContext<2, 1> {
+        TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .to(accs[0].address)
+                    .from(accs[1].address)
+                    .gas(Word::from(intrinsic_gas(&call_data)))
+                    .input(call_data.into());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+    }
+
+    fn test_exact_intrinsic_gas(call_data: Vec<u8>) {
+        CircuitTestBuilder::new_from_test_ctx(exact_intrinsic_gas_ctx(call_data))
+            .block_modifier(Box::new(|block| {
+                let tx = &block.txs[0];
+                assert_eq!(tx.call_data_gas_cost + GasCost::TX.as_u64(), tx.gas);
+                // BeginTx, EndTx, ...
+                assert_eq!(tx.steps[1].gas_left, 0);
+            }))
+            .run();
+    }
+
+    #[test]
+    fn begin_tx_intrinsic_gas_all_zero_call_data() {
+        test_exact_intrinsic_gas(vec![0; 64]);
+        test_ok(
+            mock_tx(eth(0), gwei(2), vec![0; 64]),
+            Some(code_with_return()),
+        );
+    }
+
+    #[test]
+    fn begin_tx_intrinsic_gas_all_nonzero_call_data() {
+        test_exact_intrinsic_gas(vec![0xff; 64]);
+        test_ok(
+            mock_tx(eth(0), gwei(2), vec![0xff; 64]),
+            Some(code_with_return()),
+        );
+    }
+
+    #[test]
+    fn begin_tx_intrinsic_gas_exact_gas_limit() {
+        test_exact_intrinsic_gas(vec![]);
+        test_exact_intrinsic_gas(vec![0, 1, 0, 2, 0, 0, 3, 0xff]);
+    }
+
+    #[test]
+    fn begin_tx_intrinsic_gas_above_gas_limit() {
+        // Lowering the gas limit of the tx one below its intrinsic gas leaves
+        // BeginTx with a negative gas_left.
+        for call_data in [vec![], vec![0, 1, 0, 2, 0, 0, 3, 0xff]] {
+            assert_gadget_rejects(
+                exact_intrinsic_gas_ctx(call_data),
+                Box::new(|block| block.txs[0].gas -= 1),
+            );
+        }
+    }
+
     #[test]
     fn begin_tx_large_nonce() {
         // This test checks that the rw table assignment and evm circuit are consistent
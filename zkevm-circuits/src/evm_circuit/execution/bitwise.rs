@@ -13,7 +13,10 @@ use crate::{
     },
     util::Expr,
 };
-use eth_types::{evm_types::OpcodeId, Field, ToLittleEndian};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToLittleEndian,
+};
 use halo2_proofs::plonk::Error;
 
 #[derive(Clone, Debug)]
@@ -60,11 +63,12 @@ impl<F: Field> ExecutionGadget<F> for BitwiseGadget<F> {
         }
 
         // State transition
+        // AND, OR and XOR all cost GAS_FASTESTSTEP.
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(3.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(1.expr()),
-            gas_left: Delta(-OpcodeId::AND.constant_gas_cost().expr()),
+            gas_left: Delta(-GasCost::FASTEST.expr()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
@@ -101,7 +105,7 @@ impl<F: Field> ExecutionGadget<F> for BitwiseGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, Word};
+    use eth_types::{bytecode, evm_types::OpcodeId, Word};
     use mock::TestContext;
 
     fn test_ok(a: Word, b: Word) {
@@ -126,6 +130,20 @@ mod test {
         .run();
     }
 
+    fn test_ok_op(opcode: OpcodeId, a: Word, b: Word) {
+        let bytecode = bytecode! {
+            PUSH32(b)
+            PUSH32(a)
+            .write_op(opcode)
+            STOP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .run();
+    }
+
     #[test]
     fn bitwise_gadget_simple() {
         test_ok(0x12_34_56.into(), 0x78_9A_BC.into());
@@ -137,4 +155,27 @@ mod test {
         let b = rand_word();
         test_ok(a, b);
     }
+
+    #[test]
+    fn bitwise_gadget_each_op() {
+        for opcode in [OpcodeId::AND, OpcodeId::OR, OpcodeId::XOR] {
+            test_ok_op(opcode, 0x12_34_56.into(), 0x78_9A_BC.into());
+        }
+    }
+
+    #[test]
+    fn bitwise_gadget_all_zeros() {
+        for opcode in [OpcodeId::AND, OpcodeId::OR, OpcodeId::XOR] {
+            test_ok_op(opcode, Word::zero(), Word::zero());
+            test_ok_op(opcode, Word::zero(), Word::MAX);
+        }
+    }
+
+    #[test]
+    fn bitwise_gadget_all_ones() {
+        for opcode in [OpcodeId::AND, OpcodeId::OR, OpcodeId::XOR] {
+            test_ok_op(opcode, Word::MAX, Word::MAX);
+            test_ok_op(opcode, Word::MAX, Word::zero());
+        }
+    }
 }
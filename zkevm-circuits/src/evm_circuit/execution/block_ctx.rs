@@ -36,7 +36,11 @@ impl<F: Field, const N_BYTES: usize> BlockCtxGadget<F, N_BYTES> {
             + (opcode.expr() - OpcodeId::COINBASE.as_u64().expr());
 
         // Lookup block table with block context ops
-        // TIMESTAMP/NUMBER/GASLIMIT, COINBASE and DIFFICULTY/BASEFEE
+        // TIMESTAMP/NUMBER/GASLIMIT, COINBASE and DIFFICULTY/BASEFEE.
+        // For the narrow ops the looked up value is rebuilt from the
+        // `N_BYTES` cells of `value`, each of which is range checked against
+        // the byte-range fixed table, so a block table entry that doesn't fit
+        // in `N_BYTES` (e.g. a 256-bit timestamp) can't be matched.
         let value_expr = if N_BYTES == N_BYTES_WORD {
             value.expr()
         } else {
@@ -92,10 +96,12 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxU64Gadget<F> {
 
         let value = block.rws[step.rw_indices[0]].stack_value();
 
+        // Only the low 8 bytes are assigned, a wider value can't satisfy the
+        // block lookup.
         self.value_u64.value.assign(
             region,
             offset,
-            Some(u64::try_from(value).unwrap().to_le_bytes()),
+            Some(value.to_le_bytes()[..N_BYTES_U64].try_into().unwrap()),
         )?;
 
         Ok(())
@@ -232,9 +238,12 @@ impl<F: Field> ExecutionGadget<F> for DifficultyGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
-    use mock::TestContext;
+    use crate::{
+        test_util::{assert_gadget_rejects, CircuitTestBuilder},
+        witness::Rw,
+    };
+    use eth_types::{bytecode, Word};
+    use mock::{test_ctx::helpers::*, TestContext};
 
     fn test_ok(bytecode: bytecode::Bytecode) {
         CircuitTestBuilder::new_from_test_ctx(
@@ -274,4 +283,79 @@ mod test {
         };
         test_ok(bytecode);
     }
+
+    // Post-merge blocks have zero difficulty, and 0x44 pushes the prevrandao
+    // of the mix hash field instead.
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn blockcxt_u256_gadget_prevrandao() {
+        use eth_types::{ToWord, H256};
+
+        let prevrandao = H256::from_low_u64_be(0x1234_5678_9abc_def0);
+        let bytecode = bytecode! {
+            DIFFICULTY
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _tx| block.difficulty(Word::zero()).mix_hash(prevrandao),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                // BeginTx, DIFFICULTY, STOP, ...
+                let step = &block.txs[0].steps[1];
+                assert_eq!(
+                    block.rws[step.rw_indices[0]].stack_value(),
+                    prevrandao.to_word()
+                );
+            }))
+            .run();
+    }
+
+    fn test_timestamp(timestamp: Word, tamper: Option<Word>) {
+        let bytecode = bytecode! {
+            TIMESTAMP
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _tx| block.timestamp(timestamp),
+        )
+        .unwrap();
+
+        match tamper {
+            Some(tampered) => assert_gadget_rejects(
+                ctx,
+                Box::new(move |block| {
+                    // Make the block table and the pushed value agree on a
+                    // timestamp that doesn't fit in 8 bytes.
+                    for ctx in block.context.ctxs.values_mut() {
+                        ctx.timestamp = tampered;
+                    }
+                    // BeginTx, TIMESTAMP, STOP, ...
+                    let (tag, idx) = block.txs[0].steps[1].rw_indices[0];
+                    if let Rw::Stack { value, .. } = &mut block.rws.0.get_mut(&tag).unwrap()[idx] {
+                        *value = tampered;
+                    }
+                }),
+            ),
+            None => CircuitTestBuilder::new_from_test_ctx(ctx).run(),
+        }
+    }
+
+    #[test]
+    fn blockcxt_u64_gadget_max_timestamp() {
+        test_timestamp(Word::from(u64::MAX), None);
+    }
+
+    #[test]
+    fn blockcxt_u64_gadget_timestamp_overflow() {
+        test_timestamp(Word::from(u64::MAX), Some(Word::from(u64::MAX) + 1));
+    }
 }
@@ -376,8 +376,14 @@ impl<F: Field> ExecutionGadget<F> for CallDataLoadGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_bytes},
+        table::RwTableTag,
+        test_util::{assert_gadget_rejects, CircuitTestBuilder},
+        witness::{Block, Rw},
+    };
     use eth_types::{bytecode, Bytecode, Word};
+    use halo2_proofs::halo2curves::bn256::Fr;
     use mock::{
         eth, generate_mock_call_bytecode, MockCallBytecodeParams, TestContext, MOCK_ACCOUNTS,
     };
@@ -453,6 +459,74 @@ mod test {
         test_internal_ok(0x1010, 0xff, Word::MAX);
     }
 
+    fn repeated_load_ctx(calldata: Vec<u8>) -> TestContext<2, 1> {
+        let code = bytecode! {
+            PUSH1(0x04)
+            CALLDATALOAD
+            PUSH1(0x04)
+            CALLDATALOAD
+            STOP
+        };
+        TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .input(calldata.into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+    }
+
+    /// Returns the index of the stack write of every CALLDATALOAD step.
+    fn calldataload_pushes(block: &Block<Fr>) -> Vec<(RwTableTag, usize)> {
+        block.txs[0]
+            .steps
+            .iter()
+            .filter(|step| step.execution_state == ExecutionState::CALLDATALOAD)
+            .map(|step| {
+                *step
+                    .rw_indices
+                    .iter()
+                    .find(|idx| idx.0 == RwTableTag::Stack && block.rws[**idx].is_write())
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn calldataload_gadget_repeated_load() {
+        let calldata = rand_bytes(0x40);
+        let expected = Word::from_big_endian(&calldata[0x04..0x24]);
+
+        CircuitTestBuilder::new_from_test_ctx(repeated_load_ctx(calldata.clone()))
+            .block_modifier(Box::new(move |block| {
+                let pushes = calldataload_pushes(block);
+                assert_eq!(pushes.len(), 2);
+                for idx in pushes {
+                    assert_eq!(block.rws[idx].stack_value(), expected);
+                }
+            }))
+            .run();
+
+        // The second load can't claim other bytes than the first one.
+        assert_gadget_rejects(
+            repeated_load_ctx(calldata),
+            Box::new(|block| {
+                let (tag, idx) = calldataload_pushes(block)[1];
+                if let Rw::Stack { value, .. } = &mut block.rws.0.get_mut(&tag).unwrap()[idx] {
+                    *value ^= Word::one();
+                }
+            }),
+        );
+    }
+
     fn initialization_bytecode() -> Bytecode {
         let memory_bytes = [0x60; 10];
         let memory_value = Word::from_big_endian(&memory_bytes);
@@ -89,9 +89,12 @@ impl<F: Field> ExecutionGadget<F> for CallerGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
-    use mock::TestContext;
+    use crate::{
+        evm_circuit::step::ExecutionState,
+        test_util::{subcall_ctx, CircuitTestBuilder},
+    };
+    use eth_types::{bytecode, evm_types::OpcodeId, ToWord, Word};
+    use mock::{TestContext, MOCK_ACCOUNTS};
 
     #[test]
     fn caller_gadget_test() {
@@ -105,4 +108,46 @@ mod test {
         )
         .run();
     }
+
+    fn test_caller_in_subcall(opcode: OpcodeId) {
+        let ctx = subcall_ctx(
+            opcode,
+            Word::zero(),
+            Word::zero(),
+            bytecode! {
+                CALLER
+                STOP
+            },
+        );
+
+        // A DELEGATECALL frame keeps the caller of the delegating contract.
+        let expected = if opcode == OpcodeId::DELEGATECALL {
+            MOCK_ACCOUNTS[2]
+        } else {
+            MOCK_ACCOUNTS[0]
+        };
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::CALLER)
+                    .unwrap();
+                assert_eq!(
+                    block.rws[step.rw_indices[1]].stack_value(),
+                    expected.to_word()
+                );
+            }))
+            .run();
+    }
+
+    #[test]
+    fn caller_gadget_call() {
+        test_caller_in_subcall(OpcodeId::CALL);
+    }
+
+    #[test]
+    fn caller_gadget_delegatecall() {
+        test_caller_in_subcall(OpcodeId::DELEGATECALL);
+    }
 }
@@ -11,7 +11,7 @@ use crate::{
                 Transition::{Delta, To},
             },
             math_gadget::{
-                ConstantDivisionGadget, IsZeroGadget, LtGadget, LtWordGadget, MinMaxGadget,
+                ConstantDivmodGadget, IsZeroGadget, LtGadget, LtWordGadget, MinMaxGadget,
             },
             memory_gadget::{CommonMemoryAddressGadget, MemoryAddressGadget},
             not, or,
@@ -67,7 +67,7 @@ pub(crate) struct CallOpGadget<F> {
     // check if insufficient balance case
     is_insufficient_balance: LtWordGadget<F>,
     is_depth_ok: LtGadget<F, N_BYTES_U64>,
-    one_64th_gas: ConstantDivisionGadget<F, N_BYTES_GAS>,
+    one_64th_gas: ConstantDivmodGadget<F, N_BYTES_GAS, 64>,
     capped_callee_gas_left: MinMaxGadget<F, N_BYTES_GAS>,
     // to handle precompile calls
     is_code_address_zero: IsZeroGadget<F>,
@@ -282,7 +282,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         // Apply EIP 150
         let gas_available = cb.curr.state.gas_left.expr() - gas_cost.clone();
         let one_64th_gas = cb.annotation("one_64th_gas", |cb| {
-            ConstantDivisionGadget::construct(cb, gas_available.clone(), 64)
+            ConstantDivmodGadget::construct(cb, gas_available.clone())
         });
         let all_but_one_64th_gas = gas_available - one_64th_gas.quotient();
         let capped_callee_gas_left =
@@ -1149,18 +1149,22 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{table::RwTableTag, test_util::CircuitTestBuilder, witness::Rw};
     use bus_mapping::circuit_input_builder::CircuitsParams;
     use eth_types::{
-        address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, ToWord, Word,
+        address, bytecode,
+        evm_types::{GasCost, OpcodeId},
+        geth_types::Account,
+        word, Address, ToWord, Word,
     };
+    use halo2_proofs::halo2curves::bn256::Fr;
     use itertools::Itertools;
     use mock::{
         test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
         TestContext,
     };
     use rayon::prelude::{ParallelBridge, ParallelIterator};
-    use std::default::Default;
+    use std::{cell::Cell, default::Default, rc::Rc};
 
     #[cfg(feature = "scroll")]
     mod scroll_imports {
@@ -1292,6 +1296,231 @@ mod test {
             .for_each(|opcode| test_ok(caller(opcode, stack, true), callee(bytecode! {}), None));
     }
 
+    #[test]
+    fn callcode_writes_caller_storage() {
+        let value = Word::from(10).pow(18.into());
+        let caller = caller(
+            &OpcodeId::CALLCODE,
+            Stack {
+                gas: 100000,
+                value,
+                ..Default::default()
+            },
+            true,
+        );
+        // The callee stores the call value in slot 0 of the current storage
+        // context, which for CALLCODE is the caller's.
+        let callee = callee(bytecode! {
+            CALLVALUE
+            PUSH1(0)
+            SSTORE
+            STOP
+        });
+        let (caller_address, callee_address) = (caller.address, callee.address);
+
+        test_ok_with_block_modifier(
+            caller,
+            callee,
+            Some(1000),
+            Box::new(move |block| {
+                let callcodes = block.txs[0]
+                    .calls
+                    .iter()
+                    .filter(|call| call.code_address == Some(callee_address))
+                    .collect_vec();
+                assert_eq!(callcodes.len(), 2);
+                for call in callcodes {
+                    // Storage context and transferred value are the caller's.
+                    assert_eq!(call.callee_address, caller_address);
+                    assert_eq!(call.caller_address, caller_address);
+                    assert_eq!(call.value, value);
+                }
+
+                let storage_writes = block.rws.0[&RwTableTag::AccountStorage]
+                    .iter()
+                    .filter(|rw| rw.is_write())
+                    .collect_vec();
+                assert_eq!(storage_writes.len(), 2);
+                for rw in storage_writes {
+                    assert_eq!(rw.address(), Some(caller_address));
+                    assert_eq!(rw.storage_key(), Some(Word::zero()));
+                    if let Rw::AccountStorage { value: stored, .. } = rw {
+                        assert_eq!(*stored, value);
+                    }
+                }
+            }),
+        );
+    }
+
+    fn staticcaller(callee_address: Address) -> Account {
+        Account {
+            address: Address::repeat_byte(0xfe),
+            balance: Word::from(10).pow(20.into()),
+            code: bytecode! {
+                PUSH1(0) // return data length
+                PUSH1(0) // return data offset
+                PUSH1(0) // call data length
+                PUSH1(0) // call data offset
+                PUSH32(callee_address.to_word())
+                PUSH32(40000) // gas
+                STATICCALL
+                POP
+                // copy the whole return data to memory
+                RETURNDATASIZE // size
+                PUSH1(0) // offset
+                PUSH1(0) // dest offset
+                RETURNDATACOPY
+                STOP
+            }
+            .into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn staticcall_return_data_copy() {
+        let return_value =
+            word!("0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef");
+        let callee = callee(bytecode! {
+            PUSH32(return_value)
+            PUSH1(0)
+            MSTORE
+            PUSH1(0x20)
+            PUSH1(0)
+            RETURN
+        });
+
+        test_ok_with_block_modifier(
+            staticcaller(callee.address),
+            callee,
+            None,
+            Box::new(|block| {
+                let tx = &block.txs[0];
+                // The callee of a non static caller is static.
+                assert!(!tx.calls[0].is_static);
+                assert!(tx.calls[1].is_static);
+                assert!(tx.calls[1].is_success);
+                assert_eq!(tx.calls[1].value, Word::zero());
+                // The caller reads the return data of the static callee.
+                let step = tx
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::RETURNDATASIZE)
+                    .unwrap();
+                assert_eq!(
+                    block.rws[step.rw_indices[1]].stack_value(),
+                    Word::from(0x20)
+                );
+                assert!(tx
+                    .steps
+                    .iter()
+                    .any(|step| step.execution_state == ExecutionState::RETURNDATACOPY));
+            }),
+        );
+    }
+
+    #[test]
+    fn staticcall_sstore_write_protection() {
+        let callee = callee(bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0)
+            SSTORE
+            STOP
+        });
+
+        test_ok_with_block_modifier(
+            staticcaller(callee.address),
+            callee,
+            None,
+            Box::new(|block| {
+                let tx = &block.txs[0];
+                assert!(tx.calls[1].is_static);
+                assert!(!tx.calls[1].is_success);
+                assert!(tx
+                    .steps
+                    .iter()
+                    .any(|step| step.execution_state == ExecutionState::ErrorWriteProtection));
+            }),
+        );
+    }
+
+    fn return_data_size_caller(opcode: &OpcodeId) -> Account {
+        let mut bytecode = bytecode! {
+            PUSH1(0) // return data length
+            PUSH1(0) // return data offset
+            PUSH1(0) // call data length
+            PUSH1(0) // call data offset
+        };
+        if opcode == &OpcodeId::CALL || opcode == &OpcodeId::CALLCODE {
+            bytecode.push(1, Word::zero()); // value
+        }
+        bytecode.append(&bytecode! {
+            PUSH32(Address::repeat_byte(0xff).to_word())
+            PUSH32(40000) // gas
+            .write_op(*opcode)
+            RETURNDATASIZE
+            STOP
+        });
+
+        Account {
+            address: Address::repeat_byte(0xfe),
+            balance: Word::from(10).pow(20.into()),
+            code: bytecode.to_vec().into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn callop_return_data_length() {
+        for (opcode, length) in TEST_CALL_OPCODES.iter().cartesian_product([0u64, 32, 100]) {
+            let callee = callee(bytecode! {
+                PUSH1(length)
+                PUSH1(0)
+                RETURN
+            });
+
+            test_ok_with_block_modifier(
+                return_data_size_caller(opcode),
+                callee,
+                None,
+                Box::new(move |block| {
+                    let tx = &block.txs[0];
+                    assert!(tx.calls[1].is_success);
+
+                    // The success flag is the only stack write of the call step.
+                    let call_step = tx
+                        .steps
+                        .iter()
+                        .find(|step| step.execution_state == ExecutionState::CALL_OP)
+                        .unwrap();
+                    let stack_writes = call_step
+                        .rw_indices
+                        .iter()
+                        .map(|idx| &block.rws[*idx])
+                        .filter(|rw| rw.tag() == RwTableTag::Stack && rw.is_write())
+                        .collect_vec();
+                    assert_eq!(stack_writes.len(), 1);
+                    assert_eq!(stack_writes[0].stack_value(), Word::one());
+
+                    // RETURNDATASIZE reads `LastCalleeReturnDataLength` and pushes it.
+                    let step = tx
+                        .steps
+                        .iter()
+                        .find(|step| step.execution_state == ExecutionState::RETURNDATASIZE)
+                        .unwrap();
+                    assert_eq!(
+                        block.rws[step.rw_indices[0]].call_context_value(),
+                        Word::from(length)
+                    );
+                    assert_eq!(
+                        block.rws[step.rw_indices[1]].stack_value(),
+                        Word::from(length)
+                    );
+                }),
+            );
+        }
+    }
+
     #[derive(Clone, Copy, Debug, Default)]
     struct Stack {
         gas: u64,
@@ -1430,6 +1659,15 @@ mod test {
     }
 
     fn test_ok(caller: Account, callee: Account, max_rws: Option<usize>) {
+        test_ok_with_block_modifier(caller, callee, max_rws, Box::new(|_| {}));
+    }
+
+    fn test_ok_with_block_modifier(
+        caller: Account,
+        callee: Account,
+        max_rws: Option<usize>,
+        block_modifier: Box<dyn Fn(&mut Block<Fr>)>,
+    ) {
         let ctx = TestContext::<3, 1>::new(
             None,
             |accs| {
@@ -1464,6 +1702,7 @@ mod test {
                 max_rws: max_rws.unwrap_or(500),
                 ..Default::default()
             })
+            .block_modifier(block_modifier)
             .run();
     }
 
@@ -1538,6 +1777,91 @@ mod test {
         );
     }
 
+    #[test]
+    fn callop_self_call() {
+        // Calls itself with the counter in the calldata decremented, until it
+        // reaches 0. After each call the frame reloads the counter it stored in
+        // its own memory, which the inner frames must not have touched.
+        let code = bytecode! {
+            PUSH1(0)
+            CALLDATALOAD
+            DUP1
+            ISZERO
+            PUSH1(33) // jump dest
+            JUMPI
+            PUSH1(1)
+            SWAP1
+            SUB
+            PUSH1(0)
+            MSTORE
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0x20) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH1(0) // value
+            ADDRESS
+            GAS
+            CALL
+            POP
+            PUSH1(0)
+            MLOAD
+            POP
+            JUMPDEST // 33
+            STOP
+        };
+        let depth = 3u64;
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .input(Word::from(depth).to_be_bytes().to_vec().into());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                max_rws: 1000,
+                ..Default::default()
+            })
+            .block_modifier(Box::new(move |block| {
+                let tx = &block.txs[0];
+                assert_eq!(tx.calls.len(), depth as usize + 1);
+                let root = &tx.calls[0];
+                for (caller, call) in tx.calls.iter().tuple_windows() {
+                    assert_eq!(call.caller_id, caller.id);
+                    assert_eq!(call.depth, caller.depth + 1);
+                    assert_eq!(call.code_hash, root.code_hash);
+                    assert_eq!(call.caller_address, root.callee_address);
+                    assert_eq!(call.callee_address, root.callee_address);
+                    assert_eq!(call.call_data_length, 0x20);
+                }
+
+                // The innermost frame returns first.
+                let reloaded = tx
+                    .steps
+                    .iter()
+                    .filter(|step| step.opcode == Some(OpcodeId::MLOAD))
+                    .map(|step| {
+                        let stack_push = step
+                            .rw_indices
+                            .iter()
+                            .rev()
+                            .find(|(tag, _)| *tag == RwTableTag::Stack)
+                            .unwrap();
+                        block.rws[*stack_push].stack_value()
+                    })
+                    .collect_vec();
+                assert_eq!(reloaded, (0..depth).map(Word::from).collect_vec());
+            }))
+            .run();
+    }
+
     #[test]
     fn call_non_exist_with_value() {
         let callee_code = bytecode! {
@@ -1560,6 +1884,131 @@ mod test {
             .run();
     }
 
+    /// Returns the gas cost of a CALL transferring `value` to either an empty
+    /// account or one with a balance.
+    fn call_gas_cost(value: u64, is_callee_empty: bool) -> u64 {
+        let callee = if is_callee_empty {
+            Address::repeat_byte(0xff)
+        } else {
+            mock::MOCK_ACCOUNTS[1]
+        };
+        let caller_code = bytecode! {
+            .op_call(0xc350, callee, value, 0x0, 0x0, 0x0, 0x0)
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(mock::MOCK_ACCOUNTS[0])
+                    .code(caller_code)
+                    .balance(mock::eth(1));
+                accs[1]
+                    .address(mock::MOCK_ACCOUNTS[1])
+                    .balance(mock::eth(1));
+                accs[2]
+                    .address(mock::MOCK_ACCOUNTS[2])
+                    .balance(mock::eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        let gas_cost = Rc::new(Cell::new(0));
+        let step_gas_cost = gas_cost.clone();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.opcode == Some(OpcodeId::CALL))
+                    .unwrap();
+                step_gas_cost.set(step.gas_cost);
+            }))
+            .run();
+        gas_cost.get()
+    }
+
+    #[test]
+    fn call_new_account_gas_cost() {
+        let call_with_value = call_gas_cost(1, false);
+        // A value transfer to an empty account creates it.
+        assert_eq!(
+            call_gas_cost(1, true),
+            call_with_value + GasCost::NEW_ACCOUNT.as_u64()
+        );
+        // No account is created without a value transfer.
+        let call_without_value = call_gas_cost(0, true);
+        assert_eq!(call_without_value, call_gas_cost(0, false));
+        assert_eq!(
+            call_with_value,
+            call_without_value + GasCost::CALL_WITH_VALUE.as_u64()
+        );
+    }
+
+    /// Runs a CALL forwarding no gas, so that the callee only runs on the
+    /// stipend given for a value transfer.
+    fn test_call_stipend(value: u64) {
+        let caller_code = bytecode! {
+            .op_call(0x0, mock::MOCK_ACCOUNTS[1], value, 0x0, 0x0, 0x0, 0x0)
+            STOP
+        };
+        let callee_code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            POP
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(mock::MOCK_ACCOUNTS[0])
+                    .code(caller_code)
+                    .balance(mock::eth(1));
+                accs[1].address(mock::MOCK_ACCOUNTS[1]).code(callee_code);
+                accs[2]
+                    .address(mock::MOCK_ACCOUNTS[2])
+                    .balance(mock::eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                let tx = &block.txs[0];
+                let callee_step = tx.steps.iter().find(|step| step.call_index == 1).unwrap();
+                let (gas_left, is_success) = if value == 0 {
+                    (0, false)
+                } else {
+                    (GAS_STIPEND_CALL_WITH_VALUE, true)
+                };
+                assert_eq!(callee_step.gas_left, gas_left);
+                assert_eq!(tx.calls[1].is_success, is_success);
+            }))
+            .run();
+    }
+
+    #[test]
+    fn call_stipend_with_value() {
+        test_call_stipend(1);
+    }
+
+    #[test]
+    fn call_no_stipend_without_value() {
+        test_call_stipend(0);
+    }
+
     // maybe consider to move to mpt_circuit module
     #[cfg(feature = "scroll")]
     #[test]
@@ -1678,9 +2127,13 @@ mod test {
 
 #[cfg(test)]
 mod test_precompiles {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{
+        evm_circuit::step::ExecutionState, table::RwTableTag, test_util::CircuitTestBuilder,
+        witness::Block,
+    };
     use bus_mapping::{circuit_input_builder::CircuitsParams, evm::PrecompileCallArgs};
-    use eth_types::{bytecode, evm_types::OpcodeId, word, Word};
+    use eth_types::{bytecode, evm_types::OpcodeId, word, Bytecode, Word};
+    use halo2_proofs::halo2curves::bn256::Fr;
 
     use mock::{
         test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
@@ -1710,6 +2163,117 @@ mod test_precompiles {
             .run();
     }
 
+    /// Returns the value pushed to the stack by the last step in
+    /// `execution_state`.
+    fn last_stack_push(block: &Block<Fr>, execution_state: ExecutionState) -> Word {
+        let step = block.txs[0]
+            .steps
+            .iter()
+            .rev()
+            .find(|step| step.execution_state == execution_state)
+            .unwrap_or_else(|| panic!("no {execution_state:?} step"));
+        let stack_push = step
+            .rw_indices
+            .iter()
+            .rev()
+            .find(|(tag, _)| *tag == RwTableTag::Stack)
+            .expect("step has no stack push");
+        block.rws[*stack_push].stack_value()
+    }
+
+    fn identity_round_trip_code(gas: u64, copy_return_data: bool) -> Bytecode {
+        let mut code = bytecode! {
+            PUSH32(word!("0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"))
+            PUSH1(0x00)
+            MSTORE
+            // The output is only reachable as return data.
+            .op_call(gas, 0x4, 0x0, 0x0, 0x20, 0x0, 0x0)
+            RETURNDATASIZE
+        };
+        if copy_return_data {
+            code.append(&bytecode! {
+                PUSH1(0x20) // size
+                PUSH1(0x00) // offset
+                PUSH1(0x40) // dest_offset
+                RETURNDATACOPY
+                PUSH1(0x40)
+                MLOAD
+            });
+        }
+        code.write_op(OpcodeId::STOP);
+        code
+    }
+
+    #[test]
+    fn call_identity_then_returndatacopy() {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(identity_round_trip_code(0xffff, true)),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                max_rws: 500,
+                ..Default::default()
+            })
+            .block_modifier(Box::new(|block| {
+                let tx = &block.txs[0];
+                assert!(tx
+                    .steps
+                    .iter()
+                    .any(|step| step.execution_state == ExecutionState::PrecompileIdentity));
+                assert!(tx.calls[1].is_success);
+                assert_eq!(last_stack_push(block, ExecutionState::CALL_OP), Word::one());
+                assert_eq!(
+                    last_stack_push(block, ExecutionState::RETURNDATASIZE),
+                    Word::from(0x20)
+                );
+                assert_eq!(
+                    last_stack_push(block, ExecutionState::MEMORY),
+                    word!("0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef")
+                );
+            }))
+            .run();
+    }
+
+    #[test]
+    fn call_identity_out_of_gas() {
+        // Below the 15 + 3 gas needed for a word of input.
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(identity_round_trip_code(0x10, false)),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                max_rws: 500,
+                ..Default::default()
+            })
+            .block_modifier(Box::new(|block| {
+                let tx = &block.txs[0];
+                assert!(tx
+                    .steps
+                    .iter()
+                    .any(|step| step.execution_state == ExecutionState::ErrorOutOfGasPrecompile));
+                assert!(!tx.calls[1].is_success);
+                assert_eq!(
+                    last_stack_push(block, ExecutionState::CALL_OP),
+                    Word::zero()
+                );
+                assert_eq!(
+                    last_stack_push(block, ExecutionState::RETURNDATASIZE),
+                    Word::zero()
+                );
+            }))
+            .run();
+    }
+
     fn test_precompile_inner(arg: PrecompileCallArgs, call_op: &OpcodeId) {
         let code = arg.with_call_op(*call_op);
         let ctx = TestContext::<2, 1>::new(
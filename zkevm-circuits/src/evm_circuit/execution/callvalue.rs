@@ -82,8 +82,15 @@ impl<F: Field> ExecutionGadget<F> for CallValueGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
+    use crate::{
+        evm_circuit::step::ExecutionState,
+        test_util::{subcall_ctx, CircuitTestBuilder},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{GasCost, OpcodeId},
+        Word,
+    };
     use mock::TestContext;
 
     #[test]
@@ -98,4 +105,45 @@ mod test {
         )
         .run();
     }
+
+    fn test_callvalue_in_subcall(opcode: OpcodeId) {
+        let (tx_value, call_value) = (Word::from(0x1234), Word::from(0x56));
+        let ctx = subcall_ctx(
+            opcode,
+            tx_value,
+            call_value,
+            bytecode! {
+                CALLVALUE
+                STOP
+            },
+        );
+
+        // A DELEGATECALL frame keeps the value of the frame that issued it.
+        let expected = if opcode == OpcodeId::DELEGATECALL {
+            tx_value
+        } else {
+            call_value
+        };
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::CALLVALUE)
+                    .unwrap();
+                assert_eq!(step.gas_cost, GasCost::QUICK.as_u64());
+                assert_eq!(block.rws[step.rw_indices[1]].stack_value(), expected);
+            }))
+            .run();
+    }
+
+    #[test]
+    fn callvalue_gadget_call() {
+        test_callvalue_in_subcall(OpcodeId::CALL);
+    }
+
+    #[test]
+    fn callvalue_gadget_delegatecall() {
+        test_callvalue_in_subcall(OpcodeId::DELEGATECALL);
+    }
 }
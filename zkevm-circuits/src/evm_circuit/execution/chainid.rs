@@ -78,21 +78,135 @@ impl<F: Field> ExecutionGadget<F> for ChainIdGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use super::ChainIdGadget;
+    use crate::{
+        evm_circuit::{execution::ExecutionGadget, table::Table},
+        test_util::{assert_gadget_rejects, CircuitTestBuilder},
+        witness::{Block, Rw},
+    };
+    use bus_mapping::evm::OpcodeId;
     use eth_types::bytecode;
+    use halo2_proofs::halo2curves::bn256::Fr;
     use mock::test_ctx::TestContext;
 
+    // Header of a post-merge block with the chain id of Scroll mainnet. The
+    // miner is the mock coinbase since the trace already credits it in EndTx.
+    const HEADER: &str = r#"{
+        "miner": "0x00000000000000000000000000000000c014ba5e",
+        "gasLimit": "0x989680",
+        "number": "0x2a5a3",
+        "timestamp": "0x6549a3c5",
+        "difficulty": "0x0",
+        "mixHash": "0x5b2bd2ad3e2e4b1a0c4e8c1a4db6a31b1c7c1b9f6d3e2b7a8c9d0e1f2a3b4c5d",
+        "chainId": "0x82750"
+    }"#;
+
     #[test]
-    fn chainid_gadget_test() {
+    fn chainid_gadget_lookups() {
+        let lookups = ChainIdGadget::<Fr>::lookups();
+        assert_eq!(
+            lookups
+                .iter()
+                .filter(|lookup| lookup.table == Table::Block)
+                .count(),
+            1
+        );
+    }
+
+    fn test_ok(minimal_fixed_table: bool) {
+        let bytecode = bytecode! {
+            #[start]
+            CHAINID
+            STOP
+        };
+
+        let mut ctb = CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .block_modifier(Box::new(|block| {
+            // BeginTx, CHAINID, STOP, ...
+            let steps = &block.txs[0].steps;
+            assert_eq!(steps[1].gas_cost, OpcodeId::CHAINID.constant_gas_cost().0);
+            assert_eq!(steps[1].expected_gas_after(), steps[2].gas_left);
+        }));
+        if minimal_fixed_table {
+            ctb = ctb.minimal_fixed_table();
+        }
+        ctb.run();
+    }
+
+    fn test_wrong_gas_left(minimal_fixed_table: bool) {
         let bytecode = bytecode! {
             #[start]
             CHAINID
             STOP
         };
 
-        CircuitTestBuilder::new_from_test_ctx(
+        let mut ctb = CircuitTestBuilder::new_from_test_ctx(
             TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
         )
-        .run();
+        .block_modifier(Box::new(|block| {
+            // BeginTx, CHAINID, STOP, ...
+            block.txs[0].steps[2].gas_left -= 1;
+        }))
+        .evm_checks(Some(Box::new(|prover, gate_rows, lookup_rows| {
+            assert!(prover
+                .verify_at_rows_par(gate_rows.iter().cloned(), lookup_rows.iter().cloned())
+                .is_err())
+        })));
+        if minimal_fixed_table {
+            ctb = ctb.minimal_fixed_table();
+        }
+        ctb.run();
+    }
+
+    fn test_json_header(push_loaded_chain_id: bool) {
+        let bytecode = bytecode! {
+            #[start]
+            CHAINID
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+        let load_header = Box::new(move |block: &mut Block<Fr>| {
+            block.set_context_from_json_header(HEADER).unwrap();
+            let chain_id = block.chain_id;
+            assert_eq!(chain_id, 534352);
+            if push_loaded_chain_id {
+                // BeginTx, CHAINID, STOP, ...
+                let (tag, idx) = block.txs[0].steps[1].rw_indices[0];
+                if let Rw::Stack { value, .. } = &mut block.rws.0.get_mut(&tag).unwrap()[idx] {
+                    *value = chain_id.into();
+                }
+            }
+        });
+        if push_loaded_chain_id {
+            CircuitTestBuilder::new_from_test_ctx(ctx)
+                .block_modifier(load_header)
+                .run();
+        } else {
+            assert_gadget_rejects(ctx, load_header);
+        }
+    }
+
+    #[test]
+    fn chainid_gadget_test() {
+        test_ok(false);
+    }
+
+    #[test]
+    fn chainid_gadget_json_header() {
+        test_json_header(true);
+        // The mock chain id pushed by the trace no longer matches the table.
+        test_json_header(false);
+    }
+
+    #[test]
+    fn chainid_gadget_minimal_fixed_table() {
+        // The minimal fixed table must accept and reject the same witnesses
+        // as the full one.
+        test_ok(true);
+        test_wrong_gas_left(false);
+        test_wrong_gas_left(true);
     }
 }
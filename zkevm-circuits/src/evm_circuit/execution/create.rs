@@ -13,7 +13,7 @@ use crate::{
                 Transition::{Delta, To},
             },
             math_gadget::{
-                ConstantDivisionGadget, ContractCreateGadget, IsZeroGadget, LtGadget, LtWordGadget,
+                ConstantDivmodGadget, ContractCreateGadget, IsZeroGadget, LtGadget, LtWordGadget,
             },
             memory_gadget::{
                 CommonMemoryAddressGadget, MemoryAddressGadget, MemoryExpansionGadget,
@@ -57,7 +57,7 @@ pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionStat
     create: ContractCreateGadget<F, IS_CREATE2>,
 
     init_code: MemoryAddressGadget<F>,
-    init_code_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    init_code_word_size: ConstantDivmodGadget<F, N_BYTES_MEMORY_ADDRESS, { N_BYTES_WORD as u64 }>,
     // Init code size must be less than or equal to 49152
     // (maximum init code size) if Shanghai, otherwise should be less than or
     // equal to 0x1FFFFFFFE0 (maximum value of offset + size).
@@ -71,7 +71,7 @@ pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionStat
     not_address_collision: IsZeroGadget<F>,
 
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
-    gas_left: ConstantDivisionGadget<F, N_BYTES_GAS>,
+    gas_left: ConstantDivmodGadget<F, N_BYTES_GAS, 64>,
     // check address collision use
     keccak_code_hash: Cell<F>,
     #[cfg(feature = "scroll")]
@@ -232,11 +232,8 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 
         // verify gas cost
         let memory_expansion = MemoryExpansionGadget::construct(cb, [init_code.end_offset()]);
-        let init_code_word_size = ConstantDivisionGadget::construct(
-            cb,
-            init_code.length() + (N_BYTES_WORD - 1).expr(),
-            N_BYTES_WORD as u64,
-        );
+        let init_code_word_size =
+            ConstantDivmodGadget::construct(cb, init_code.length() + (N_BYTES_WORD - 1).expr());
         let keccak_gas_cost = init_code_word_size.quotient()
             * if IS_CREATE2 {
                 CREATE2_GAS_PER_CODE_WORD
@@ -247,7 +244,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
         let gas_remaining = cb.curr.state.gas_left.expr() - gas_cost.clone();
-        let gas_left = ConstantDivisionGadget::construct(cb, gas_remaining.clone(), 64);
+        let gas_left = ConstantDivmodGadget::construct(cb, gas_remaining.clone());
         let callee_gas_left = gas_remaining - gas_left.quotient();
 
         let was_warm = cb.query_bool();
@@ -831,11 +828,15 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{
+        evm_circuit::step::ExecutionState, table::RwTableTag, test_util::CircuitTestBuilder,
+    };
     use bus_mapping::circuit_input_builder::CircuitsParams;
     use eth_types::{
-        address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, Bytecode, Word,
+        address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, Bytecode,
+        ToWord, Word,
     };
+    use ethers_core::utils::get_contract_address;
     use itertools::Itertools;
     use mock::{eth, TestContext, MOCK_ACCOUNTS};
     use std::sync::LazyLock;
@@ -1063,6 +1064,135 @@ mod test {
         run_test_circuits(test_context(caller));
     }
 
+    // CREATE to an address that already has a nonce or code must fail and push 0
+    // while the caller nonce is still incremented.
+    fn test_create_collision(callee_nonce: u64, callee_code: Bytecode) {
+        let caller_nonce = 1;
+        let callee_address = get_contract_address(*CALLER_ADDRESS, caller_nonce);
+        let caller = Account {
+            address: *CALLER_ADDRESS,
+            code: creator_bytecode(initialization_bytecode(true), 23414.into(), false, true).into(),
+            nonce: caller_nonce.into(),
+            balance: eth(10),
+            ..Default::default()
+        };
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1].account(&caller);
+                accs[2]
+                    .address(callee_address)
+                    .nonce(callee_nonce.into())
+                    .code(callee_code);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(word!("0x2386F26FC10000"));
+            },
+            |block, _| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::CREATE)
+                    .unwrap();
+                // 0..4 : call context, 5..7 : stack inputs, 8 : stack output
+                assert_eq!(block.rws[step.rw_indices[8]].stack_value(), Word::zero());
+                // 9 : caller address, 10 : balance, 11 : nonce, 12 : nonce += 1
+                assert_eq!(
+                    block.rws[step.rw_indices[12]].account_nonce_pair(),
+                    (Word::from(2), Word::one())
+                );
+            }))
+            .run();
+    }
+
+    // CREATE pushes the new address on success, and 0 if the initialization
+    // code reverts, which EXTCODESIZE then reads back.
+    fn test_create_extcodesize(is_success: bool) {
+        let caller_nonce = 1u64;
+        let init_bytes = initialization_bytecode(is_success).code();
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&init_bytes))
+            PUSH1(0)
+            MSTORE
+            PUSH1(init_bytes.len()) // length
+            PUSH1(32 - init_bytes.len()) // offset
+            PUSH1(0) // value
+            CREATE
+            DUP1
+            EXTCODESIZE
+            STOP
+        };
+        let caller = Account {
+            address: *CALLER_ADDRESS,
+            code: code.into(),
+            nonce: caller_nonce.into(),
+            balance: eth(10),
+            ..Default::default()
+        };
+        let (address, code_size) = if is_success {
+            (
+                get_contract_address(*CALLER_ADDRESS, caller_nonce).to_word(),
+                5,
+            )
+        } else {
+            (Word::zero(), 0)
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(test_context(caller))
+            .params(CircuitsParams {
+                max_rws: 0, // dynamic
+                max_copy_rows: 140_000,
+                ..Default::default()
+            })
+            .block_modifier(Box::new(move |block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::EXTCODESIZE)
+                    .unwrap();
+                let mut stack_values = step
+                    .rw_indices
+                    .iter()
+                    .filter(|(tag, _)| *tag == RwTableTag::Stack)
+                    .map(|idx| block.rws[*idx].stack_value());
+                assert_eq!(stack_values.next(), Some(address));
+                assert_eq!(stack_values.last(), Some(Word::from(code_size)));
+                // The deployed code is in the bytecode table.
+                assert_eq!(
+                    block.bytecodes.values().any(|code| code.bytes == [0x60; 5]),
+                    is_success
+                );
+            }))
+            .run();
+    }
+
+    #[test]
+    fn test_create_push_address() {
+        test_create_extcodesize(true);
+        test_create_extcodesize(false);
+    }
+
+    #[test]
+    fn test_create_nonce_collision() {
+        test_create_collision(1, Bytecode::default());
+    }
+
+    #[test]
+    fn test_create_codehash_collision() {
+        test_create_collision(0, bytecode! { STOP });
+    }
+
     #[ignore]
     #[test]
     fn test_create_2tx_at_same_address() {
@@ -1170,7 +1300,35 @@ mod test {
             balance: eth(10),
             ..Default::default()
         };
-        run_test_circuits(test_context(caller));
+        // Every init code copies itself and CREATEs again, so the nesting stops
+        // at the first CREATE issued from depth 1025, which must push 0 without
+        // entering another call.
+        CircuitTestBuilder::new_from_test_ctx(test_context(caller))
+            .params(CircuitsParams {
+                max_rws: 0, // dynamic
+                max_copy_rows: 140_000,
+                ..Default::default()
+            })
+            .block_modifier(Box::new(|block| {
+                let tx = &block.txs[0];
+                let max_depth = tx.calls.iter().map(|call| call.depth).max().unwrap();
+                assert_eq!(max_depth, 1025);
+
+                let deepest_create = tx
+                    .steps
+                    .iter()
+                    .find(|step| {
+                        step.execution_state == ExecutionState::CREATE
+                            && tx.calls[step.call_index].depth == 1025
+                    })
+                    .expect("CREATE at max depth");
+                // 0..4 : call context, 5..7 : stack inputs, 8 : stack output
+                assert_eq!(
+                    block.rws[deepest_create.rw_indices[8]].stack_value(),
+                    Word::zero()
+                );
+            }))
+            .run();
     }
 
     #[test]
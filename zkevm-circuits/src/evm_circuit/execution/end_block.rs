@@ -212,7 +212,10 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{
+        test_util::{assert_gadget_rejects, CircuitTestBuilder},
+        witness::WitnessError,
+    };
 
     use eth_types::bytecode;
 
@@ -247,4 +250,44 @@ mod test {
     fn end_block_padding() {
         test_circuit(100);
     }
+
+    // Test where the block has more rws than the RW table can hold.
+    #[test]
+    fn end_block_rw_overflow() {
+        let bytecode = bytecode! {
+            PUSH1(0)
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+
+        let result = CircuitTestBuilder::<2, 1>::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| block.circuits_params.max_rws = 8))
+            .try_run();
+        assert!(
+            matches!(result, Err(WitnessError::RwOverflow { max: 8, .. })),
+            "{result:?}"
+        );
+    }
+
+    // Test where the EndBlock steps claim one more rw than the RW table
+    // holds, as if its last row had been truncated.
+    #[test]
+    fn end_block_truncated_rw_table() {
+        let bytecode = bytecode! {
+            PUSH1(0)
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+
+        assert_gadget_rejects(
+            ctx,
+            Box::new(|block| {
+                block.circuits_params.max_evm_rows = 100;
+                block.end_block_not_last.rw_counter += 1;
+                block.end_block_last.rw_counter += 1;
+            }),
+        );
+    }
 }
@@ -24,9 +24,7 @@ use crate::{
     },
     util::Expr,
 };
-use eth_types::{
-    evm_types::MAX_REFUND_QUOTIENT_OF_GAS_USED, geth_types::TxType, Field, ToLittleEndian, ToScalar,
-};
+use eth_types::{geth_types::TxType, Field, ToLittleEndian, ToScalar};
 use gadgets::util::{not, select};
 use halo2_proofs::{circuit::Value, plonk::Error};
 use strum::EnumCount;
@@ -40,12 +38,12 @@ pub(crate) struct EndTxGadget<F> {
     refund: Cell<F>,
     effective_refund: MinMaxGadget<F, N_BYTES_GAS>,
     effective_fee: Word<F>,
-    mul_gas_price_by_refund: MulWordByU64Gadget<F>,
+    mul_gas_price_by_refund: MulWordByU64Gadget<F, true>,
     tx_caller_address: Cell<F>,
     tx_data_gas_cost: Cell<F>,
     gas_fee_refund: UpdateBalanceGadget<F, 2, true>,
     sub_gas_price_by_base_fee: AddWordsGadget<F, 2, true>,
-    mul_effective_tip_by_gas_used: MulWordByU64Gadget<F>,
+    mul_effective_tip_by_gas_used: MulWordByU64Gadget<F, true>,
     coinbase: Cell<F>,
     coinbase_codehash: Cell<F>,
     #[cfg(feature = "scroll")]
@@ -84,11 +82,9 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 
         // Calculate effective gas to refund
         let gas_used = tx_gas.expr() - cb.curr.state.gas_left.expr();
-        let max_refund = ConstantDivisionGadget::construct(
-            cb,
-            gas_used.clone(),
-            MAX_REFUND_QUOTIENT_OF_GAS_USED as u64,
-        );
+        let max_refund_quotient = cb.hard_fork().max_refund_quotient();
+        let max_refund =
+            ConstantDivisionGadget::construct(cb, gas_used.clone(), max_refund_quotient);
         let refund = cb.query_cell();
         cb.tx_refund_read(tx_id.expr(), refund.expr());
         // rwc_delta = 4
@@ -478,11 +474,22 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{
+        evm_circuit::step::ExecutionState,
+        table::{AccountFieldTag, RwTableTag},
+        test_util::CircuitTestBuilder,
+        witness::Rw,
+    };
     use bus_mapping::circuit_input_builder::CircuitsParams;
-    use eth_types::{self, bytecode};
+    use eth_types::{
+        self, bytecode,
+        evm_types::{GasCost, OpcodeId, MAX_REFUND_QUOTIENT_OF_GAS_USED},
+        Bytecode, Word,
+    };
 
-    use mock::{eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
 
     fn test_ok<const NACC: usize, const NTX: usize>(ctx: TestContext<NACC, NTX>) {
         CircuitTestBuilder::new_from_test_ctx(ctx)
@@ -562,4 +569,113 @@ mod test {
             .unwrap(),
         );
     }
+
+    /// Checks that the sender ends up with
+    /// `initial - value - gas_used * gas_price + refund * gas_price`.
+    fn test_sender_final_balance(code: Bytecode, gas: u64, uses_all_gas: bool, has_refund: bool) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code)
+                    .storage([(Word::zero(), Word::one())].into_iter());
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .to(accs[0].address)
+                    .from(accs[1].address)
+                    .gas(gas.into())
+                    .value(eth(1));
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                let tx = &block.txs[0];
+                let end_tx = tx
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::EndTx)
+                    .unwrap();
+                let gas_used = tx.gas - end_tx.gas_left;
+                let refund = end_tx
+                    .rw_indices
+                    .iter()
+                    .find(|(tag, _)| *tag == RwTableTag::TxRefund)
+                    .map(|idx| block.rws[*idx].tx_refund_value_pair().0)
+                    .unwrap();
+                let effective_refund =
+                    refund.min(gas_used / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64);
+                assert_eq!(end_tx.gas_left == 0, uses_all_gas);
+                assert_eq!(effective_refund > 0, has_refund);
+
+                let final_balance = block
+                    .rws
+                    .table_assignments_unsorted()
+                    .into_iter()
+                    .filter(|rw| {
+                        matches!(
+                            rw,
+                            Rw::Account {
+                                account_address,
+                                field_tag: AccountFieldTag::Balance,
+                                ..
+                            } if *account_address == MOCK_ACCOUNTS[1]
+                        )
+                    })
+                    .max_by_key(|rw| rw.rw_counter())
+                    .map(|rw| rw.account_value_pair().0)
+                    .unwrap();
+                let l1_fee = Word::from(tx.l1_fee.tx_l1_fee(tx.tx_data_gas_cost).0);
+                assert_eq!(
+                    final_balance,
+                    eth(10) - tx.value - tx.gas_price * (gas_used - effective_refund) - l1_fee
+                );
+            }))
+            .run();
+    }
+
+    #[test]
+    fn end_tx_sender_balance_partial_gas() {
+        test_sender_final_balance(bytecode! { STOP }, 30000, false, false);
+    }
+
+    #[test]
+    fn end_tx_sender_balance_all_gas() {
+        let gas = GasCost::TX.as_u64()
+            + 2 * OpcodeId::PUSH1.constant_gas_cost().as_u64()
+            + OpcodeId::ADD.constant_gas_cost().as_u64();
+        test_sender_final_balance(
+            bytecode! {
+                PUSH1(0x01)
+                PUSH1(0x02)
+                ADD
+                STOP
+            },
+            gas,
+            true,
+            false,
+        );
+    }
+
+    #[test]
+    fn end_tx_sender_balance_refund() {
+        // Clearing the storage slot earns a refund, capped by the gas used.
+        test_sender_final_balance(
+            bytecode! {
+                PUSH1(0x00)
+                PUSH1(0x00)
+                SSTORE
+                STOP
+            },
+            100000,
+            false,
+            true,
+        );
+    }
 }
@@ -134,7 +134,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorCodeStoreGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{
+        evm_circuit::step::ExecutionState,
+        table::{AccountFieldTag, RwTableTag},
+        test_util::CircuitTestBuilder,
+        witness::Rw,
+    };
     use bus_mapping::circuit_input_builder::CircuitsParams;
     use eth_types::{
         address,
@@ -278,6 +283,66 @@ mod test {
         }
     }
 
+    fn test_create_code_deposit(is_affordable: bool, is_create2: bool) {
+        let initialization_code = initialization_bytecode(true);
+        let root_code = creator_bytecode(initialization_code, is_create2);
+        let caller = Account {
+            address: *CALLER_ADDRESS,
+            code: root_code.into(),
+            nonce: Word::one(),
+            balance: eth(10),
+            ..Default::default()
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(test_context(caller, !is_affordable))
+            .params(CircuitsParams {
+                max_rws: 4500,
+                ..Default::default()
+            })
+            .block_modifier(Box::new(move |block| {
+                let steps = &block.txs[0].steps;
+                assert_eq!(
+                    steps
+                        .iter()
+                        .any(|step| step.execution_state == ExecutionState::ErrorCodeStore),
+                    !is_affordable
+                );
+
+                let create_step = steps
+                    .iter()
+                    .find(|step| matches!(step.opcode, Some(OpcodeId::CREATE | OpcodeId::CREATE2)))
+                    .unwrap();
+                // The creator's nonce is incremented whether or not deployment succeeds.
+                assert!(create_step.rw_indices.iter().any(|idx| matches!(
+                    block.rws[*idx],
+                    Rw::Account {
+                        account_address,
+                        field_tag: AccountFieldTag::Nonce,
+                        value,
+                        ..
+                    } if account_address == *CALLER_ADDRESS && value == Word::from(2)
+                )));
+                // The pushed address is 0 when the code deposit is unaffordable.
+                let pushed = create_step
+                    .rw_indices
+                    .iter()
+                    .filter(|(tag, _)| *tag == RwTableTag::Stack)
+                    .last()
+                    .map(|idx| block.rws[*idx].stack_value())
+                    .unwrap();
+                assert_eq!(pushed.is_zero(), !is_affordable);
+            }))
+            .run();
+    }
+
+    #[test]
+    fn test_create_code_deposit_affordable_and_oog() {
+        for is_create2 in [false, true] {
+            test_create_code_deposit(true, is_create2);
+            test_create_code_deposit(false, is_create2);
+        }
+    }
+
     #[test]
     fn tx_deploy_code_store_oog() {
         let code = initialization_bytecode(true);
@@ -161,7 +161,7 @@ mod test {
 
     use mock::{eth, TestContext, MOCK_ACCOUNTS};
 
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{evm_circuit::step::ExecutionState, test_util::CircuitTestBuilder};
 
     const CALLEE_ADDRESS: Address = Address::repeat_byte(0xff);
     static CALLER_ADDRESS: LazyLock<Address> =
@@ -271,6 +271,54 @@ mod test {
         }
     }
 
+    /// Initialization code returning the single byte `first_byte` as the
+    /// code to deploy.
+    fn single_byte_initialization_bytecode(first_byte: u8) -> Bytecode {
+        bytecode! {
+            PUSH1(first_byte)
+            PUSH1(0)
+            MSTORE8
+            PUSH1(1)
+            PUSH1(0)
+            RETURN
+        }
+    }
+
+    #[test]
+    fn test_creation_code_first_byte() {
+        for (first_byte, is_create2) in [0xef, 0x00, 0x60, 0xee, 0xfe]
+            .into_iter()
+            .flat_map(|first_byte| [(first_byte, false), (first_byte, true)])
+        {
+            let root_code =
+                creator_bytecode(single_byte_initialization_bytecode(first_byte), is_create2);
+            let caller = Account {
+                address: *CALLER_ADDRESS,
+                code: root_code.into(),
+                nonce: Word::one(),
+                balance: eth(10),
+                ..Default::default()
+            };
+
+            CircuitTestBuilder::new_from_test_ctx(test_context(caller))
+                .params(CircuitsParams {
+                    max_rws: 4500,
+                    ..Default::default()
+                })
+                .block_modifier(Box::new(move |block| {
+                    let tx = &block.txs[0];
+                    let is_rejected = tx.steps.iter().any(|step| {
+                        step.execution_state == ExecutionState::ErrorInvalidCreationCode
+                    });
+                    assert_eq!(is_rejected, first_byte == 0xef);
+                    // Only the deployment fails, the creator call goes on.
+                    assert!(tx.calls[0].is_success);
+                    assert_eq!(tx.calls[1].is_success, first_byte != 0xef);
+                }))
+                .run();
+        }
+    }
+
     // add tx deploy case for invalid creation code.
     #[test]
     fn test_tx_deploy_invalid_creation_code() {
@@ -1,10 +1,9 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::N_BYTES_PROGRAM_COUNTER,
         step::ExecutionState,
         util::{
-            common_gadget::{CommonErrorGadget, WordByteCapGadget},
+            common_gadget::{CommonErrorGadget, JumpDestValidGadget},
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{IsEqualGadget, IsZeroGadget},
             CachedRegion, Cell,
@@ -20,12 +19,7 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorInvalidJumpGadget<F> {
     opcode: Cell<F>,
-    dest: WordByteCapGadget<F, N_BYTES_PROGRAM_COUNTER>,
-    code_len: Cell<F>,
-    value: Cell<F>,
-    is_code: Cell<F>,
-    push_rlc: Cell<F>,
-    is_jump_dest: IsEqualGadget<F>,
+    dest: JumpDestValidGadget<F>,
     is_jumpi: IsEqualGadget<F>,
     phase2_condition: Cell<F>,
     is_condition_zero: IsZeroGadget<F>,
@@ -38,13 +32,9 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorInvalidJump;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let code_len = cb.query_cell();
-        let dest = WordByteCapGadget::construct(cb, code_len.expr());
+        let dest = JumpDestValidGadget::construct(cb);
 
         let opcode = cb.query_cell();
-        let value = cb.query_cell();
-        let is_code = cb.query_cell();
-        let push_rlc = cb.query_cell_phase2();
         let phase2_condition = cb.query_cell_phase2();
 
         cb.require_in_set(
@@ -55,9 +45,6 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
 
         let is_jumpi = IsEqualGadget::construct(cb, opcode.expr(), OpcodeId::JUMPI.expr());
 
-        // initialize is_jump_dest
-        let is_jump_dest = IsEqualGadget::construct(cb, value.expr(), OpcodeId::JUMPDEST.expr());
-
         // first default this condition, if use will re-construct with real condition
         // value
         let is_condition_zero = IsZeroGadget::construct(cb, phase2_condition.expr());
@@ -71,23 +58,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
             cb.require_zero("condition is not zero", is_condition_zero.expr());
         });
 
-        // Look up bytecode length
-        cb.bytecode_length(cb.curr.state.code_hash.expr(), code_len.expr());
-
-        // If destination is in valid range, lookup for the value.
-        cb.condition(dest.lt_cap(), |cb| {
-            cb.bytecode_lookup(
-                cb.curr.state.code_hash.expr(),
-                dest.valid_value(),
-                is_code.expr(),
-                value.expr(),
-                push_rlc.expr(),
-            );
-            cb.require_zero(
-                "is_code is false or not JUMPDEST",
-                is_code.expr() * is_jump_dest.expr(),
-            );
-        });
+        // The destination is out of range, or is_code is false or not JUMPDEST.
+        cb.require_zero("destination is not a valid JUMPDEST", dest.is_valid());
 
         let common_error_gadget =
             CommonErrorGadget::construct(cb, opcode.expr(), 3.expr() + is_jumpi.expr());
@@ -95,11 +67,6 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
         Self {
             opcode,
             dest,
-            code_len,
-            value,
-            is_code,
-            push_rlc,
-            is_jump_dest,
             is_jumpi,
             phase2_condition,
             is_condition_zero,
@@ -128,36 +95,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
         };
         let condition_rlc = region.word_rlc(condition);
 
-        let code = block
-            .bytecodes
-            .get(&call.code_hash)
-            .expect("could not find current environment's bytecode");
-        let code_len = code.bytes.len() as u64;
-        self.code_len
-            .assign(region, offset, Value::known(F::from(code_len)))?;
-
         let dest = block.rws[step.rw_indices[0]].stack_value();
-        self.dest.assign(region, offset, dest, F::from(code_len))?;
-
-        // set default value in case can not find value, is_code from bytecode table
-        let dest = u64::try_from(dest).unwrap_or(code_len);
-        let mut code_pair = (0u8, false, Value::known(F::zero()));
-        if dest < code_len {
-            // get real value from bytecode table
-            code_pair = code.get_byte_row(dest as usize, region.challenges());
-        }
-
-        self.value
-            .assign(region, offset, Value::known(F::from(code_pair.0 as u64)))?;
-        self.is_code
-            .assign(region, offset, Value::known(F::from(code_pair.1)))?;
-        self.push_rlc.assign(region, offset, code_pair.2)?;
-        self.is_jump_dest.assign(
-            region,
-            offset,
-            F::from(code_pair.0 as u64),
-            F::from(OpcodeId::JUMPDEST.as_u64()),
-        )?;
+        self.dest.assign(region, offset, block, call, dest)?;
 
         self.is_jumpi.assign(
             region,
@@ -187,14 +126,32 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
 #[cfg(test)]
 mod test {
 
-    use crate::test_util::CircuitTestBuilder;
+    use super::ErrorInvalidJumpGadget;
+    use crate::{
+        evm_circuit::{
+            execution::ExecutionGadget, param::N_BYTES_PROGRAM_COUNTER, step::ExecutionState,
+            table::Table,
+        },
+        test_util::{assert_gadget_rejects, nested_call_ctx, CircuitTestBuilder},
+    };
     use eth_types::{
         address, bytecode, bytecode::Bytecode, evm_types::OpcodeId, geth_types::Account, Address,
         ToWord, Word,
     };
+    use halo2_proofs::halo2curves::bn256::Fr;
 
     use mock::TestContext;
 
+    #[test]
+    fn invalid_jump_lookups() {
+        let lookups = ErrorInvalidJumpGadget::<Fr>::lookups();
+        for name in ["Bytecode (length)", "Bytecode (byte) lookup"] {
+            assert!(lookups
+                .iter()
+                .any(|lookup| lookup.table == Table::Bytecode && lookup.name == name));
+        }
+    }
+
     fn test_invalid_jump(destination: usize, out_of_range: bool) {
         let mut bytecode = bytecode! {
             PUSH32(if out_of_range { destination + 10} else { destination })
@@ -234,6 +191,26 @@ mod test {
         test_internal_jump_error(true);
     }
 
+    #[test]
+    fn invalid_jump_nested_call() {
+        let inner = bytecode! {
+            PUSH1(0xff)
+            JUMP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(nested_call_ctx::<5>(3, inner))
+            .block_modifier(Box::new(|block| {
+                let tx = &block.txs[0];
+                let step = tx
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::ErrorInvalidJump)
+                    .unwrap();
+                assert_eq!(tx.calls[step.call_index].depth, 4);
+            }))
+            .run();
+    }
+
     #[test]
     fn invalid_jump_dest_overflow() {
         let bytecode = bytecode! {
@@ -247,6 +224,174 @@ mod test {
         .run();
     }
 
+    #[test]
+    fn invalid_jump_dest_aliasing_jumpdest() {
+        // The destination 2^64 truncated to the program counter bytes is the
+        // JUMPDEST at 0, so the upper bytes must make it out of range.
+        let dest = Word::one() << (8 * N_BYTES_PROGRAM_COUNTER);
+        for code in [
+            bytecode! {
+                JUMPDEST
+                PUSH32(dest)
+                JUMP
+            },
+            bytecode! {
+                JUMPDEST
+                PUSH1(1)
+                PUSH32(dest)
+                JUMPI
+            },
+        ] {
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+            )
+            .block_modifier(Box::new(|block| {
+                assert!(block.txs[0]
+                    .steps
+                    .iter()
+                    .any(|step| step.execution_state == ExecutionState::ErrorInvalidJump));
+            }))
+            .run();
+        }
+    }
+
+    #[test]
+    fn invalid_jump_into_push_data() {
+        // The destination is a JUMPDEST byte that is push data.
+        let bytecode = || {
+            bytecode! {
+                PUSH1(4)
+                JUMP
+                PUSH1(OpcodeId::JUMPDEST.as_u64())
+                STOP
+            }
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode()).unwrap(),
+        )
+        .run();
+
+        // Replacing the PUSH1 by STOP flips `is_code` of the destination, which
+        // makes it a valid jump, so the error step must be rejected.
+        assert_gadget_rejects(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode()).unwrap(),
+            Box::new(|block| {
+                let code_hash = block.txs[0].calls[0].code_hash;
+                let code = block.bytecodes.get_mut(&code_hash).unwrap();
+                assert_eq!(code.bytes[3], OpcodeId::PUSH1.as_u8());
+                code.bytes[3] = OpcodeId::STOP.as_u8();
+            }),
+        );
+    }
+
+    /// Runs `valid` with a valid destination, and `into_push_data` jumping
+    /// into push data, which becomes `valid` once the push opcode at
+    /// `push_index` is replaced by STOP. The error gadget must reject the
+    /// destination that JUMP and JUMPI accept.
+    fn test_valid_dest_rejected(valid: Bytecode, into_push_data: Bytecode, push_index: usize) {
+        let mut tampered = into_push_data.code();
+        tampered[push_index] = OpcodeId::STOP.as_u8();
+        assert_eq!(tampered, valid.code());
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(valid).unwrap(),
+        )
+        .run();
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(into_push_data.clone()).unwrap(),
+        )
+        .run();
+
+        assert_gadget_rejects(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(into_push_data).unwrap(),
+            Box::new(move |block| {
+                let code_hash = block.txs[0].calls[0].code_hash;
+                block.bytecodes.get_mut(&code_hash).unwrap().bytes[push_index] =
+                    OpcodeId::STOP.as_u8();
+            }),
+        );
+    }
+
+    #[test]
+    fn invalid_jump_rejects_valid_dest() {
+        test_valid_dest_rejected(
+            bytecode! {
+                PUSH1(4)
+                JUMP
+                STOP
+                JUMPDEST
+                STOP
+            },
+            bytecode! {
+                PUSH1(4)
+                JUMP
+                PUSH1(OpcodeId::JUMPDEST.as_u64())
+                STOP
+            },
+            3,
+        );
+        test_valid_dest_rejected(
+            bytecode! {
+                PUSH1(1)
+                PUSH1(6)
+                JUMPI
+                STOP
+                JUMPDEST
+                STOP
+            },
+            bytecode! {
+                PUSH1(1)
+                PUSH1(6)
+                JUMPI
+                PUSH1(OpcodeId::JUMPDEST.as_u64())
+                STOP
+            },
+            5,
+        );
+    }
+
+    #[test]
+    fn invalid_jump_rejects_claim_on_valid_jump() {
+        // The JUMP and JUMPI to a JUMPDEST can't be proven as ErrorInvalidJump,
+        // since their destinations are looked up in the actual bytecode.
+        for (code, state) in [
+            (
+                bytecode! {
+                    PUSH1(4)
+                    JUMP
+                    STOP
+                    JUMPDEST
+                    STOP
+                },
+                ExecutionState::JUMP,
+            ),
+            (
+                bytecode! {
+                    PUSH1(1)
+                    PUSH1(6)
+                    JUMPI
+                    STOP
+                    JUMPDEST
+                    STOP
+                },
+                ExecutionState::JUMPI,
+            ),
+        ] {
+            assert_gadget_rejects(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+                Box::new(move |block| {
+                    let step = block.txs[0]
+                        .steps
+                        .iter_mut()
+                        .find(|step| step.execution_state == state)
+                        .unwrap();
+                    step.execution_state = ExecutionState::ErrorInvalidJump;
+                }),
+            );
+        }
+    }
+
     // internal call test
     struct Stack {
         gas: u64,
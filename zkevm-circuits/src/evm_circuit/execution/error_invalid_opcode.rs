@@ -70,10 +70,15 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode::Bytecode, Word};
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_bytes},
+        test_util::CircuitTestBuilder,
+    };
+    use eth_types::{bytecode, bytecode::Bytecode, evm_types::OpcodeId, ToWord, Word};
     use mock::{generate_mock_call_bytecode, MockCallBytecodeParams, TestContext};
 
+    use std::str::FromStr;
+
     #[cfg(feature = "scroll")]
     use eth_types::address;
 
@@ -127,6 +132,65 @@ mod test {
         test_internal_ok(0x20, 0x30, &[fe_opcode]);
     }
 
+    #[test]
+    fn invalid_opcode_burns_all_gas() {
+        // Includes INVALID (0xfe) along with every undefined opcode.
+        for invalid_opcode in OpcodeId::invalid_opcodes().iter().map(OpcodeId::as_u8) {
+            let mut code = Bytecode::default();
+            code.push(1, Word::one());
+            code.write(invalid_opcode, true);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+            )
+            .block_modifier(Box::new(move |block| {
+                let tx = &block.txs[0];
+                let (error_step, next_step) = tx
+                    .steps
+                    .iter()
+                    .zip(tx.steps.iter().skip(1))
+                    .find(|(step, _)| step.execution_state == ExecutionState::ErrorInvalidOpcode)
+                    .unwrap_or_else(|| panic!("opcode {invalid_opcode:#x} is not rejected"));
+                assert_eq!(error_step.opcode, Some(OpcodeId::from(invalid_opcode)));
+                // The root call halts, burning all of its remaining gas.
+                assert_eq!(next_step.execution_state, ExecutionState::EndTx);
+                assert_eq!(next_step.gas_left, 0);
+                assert!(!tx.calls[0].is_success);
+            }))
+            .run();
+        }
+    }
+
+    // Blob (EIP-4844) txs aren't supported, there is no tx type for them in
+    // the tx circuit and no versioned hashes to push, so BLOBHASH must halt as
+    // an undefined opcode.
+    #[test]
+    fn invalid_opcode_blobhash() {
+        let blobhash = 0x49;
+        assert!(matches!(OpcodeId::from(blobhash), OpcodeId::INVALID(_)));
+        test_root_ok(&[blobhash]);
+        test_internal_ok(0x20, 0x00, &[blobhash]);
+    }
+
+    // Transient storage (EIP-1153) isn't supported, so TLOAD and TSTORE halt
+    // as undefined opcodes, also in a static call where TSTORE would otherwise
+    // be a write protection error.
+    #[test]
+    fn invalid_opcode_transient_storage_in_static_call() {
+        for (tload_or_tstore, name) in [(0x5c, "TLOAD"), (0x5d, "TSTORE")] {
+            assert_eq!(
+                OpcodeId::from_str(name).unwrap(),
+                OpcodeId::from(tload_or_tstore)
+            );
+            assert!(matches!(
+                OpcodeId::from(tload_or_tstore),
+                OpcodeId::INVALID(_)
+            ));
+            test_root_ok(&[tload_or_tstore]);
+            test_static_call_ok(tload_or_tstore);
+        }
+    }
+
     #[cfg(not(feature = "shanghai"))]
     #[test]
     fn invalid_opcode_push0_for_not_shanghai() {
@@ -227,4 +291,54 @@ mod test {
 
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
+
+    fn test_static_call_ok(invalid_opcode: u8) {
+        let (addr_a, addr_b) = (mock::MOCK_ACCOUNTS[0], mock::MOCK_ACCOUNTS[1]);
+
+        let mut code_b = bytecode! {
+            PUSH1(0x01) // value
+            PUSH1(0x00) // key
+        };
+        code_b.write(invalid_opcode, true);
+
+        // code A STATICCALLs code B.
+        let code_a = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH20(addr_b.to_word()) // addr
+            PUSH2(0xffff) // gas
+            STATICCALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(addr_b).code(code_b);
+                accs[1].address(addr_a).code(code_a);
+                accs[2]
+                    .address(mock::MOCK_ACCOUNTS[3])
+                    .balance(Word::from(1_u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[1].address).from(accs[2].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                let tx = &block.txs[0];
+                let step = tx
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::ErrorInvalidOpcode)
+                    .unwrap();
+                assert!(tx.calls[step.call_index].is_static);
+            }))
+            .run();
+    }
 }
@@ -232,10 +232,13 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{evm_circuit::step::ExecutionState, test_util::CircuitTestBuilder};
     use eth_types::{
-        address, bytecode, bytecode::Bytecode, evm_types::OpcodeId, geth_types::Account, Address,
-        ToWord, Word,
+        address, bytecode,
+        bytecode::Bytecode,
+        evm_types::{gas_utils::memory_expansion_gas_cost, GasCost, OpcodeId},
+        geth_types::Account,
+        Address, ToWord, Word,
     };
     use mock::TestContext;
     use std::default::Default;
@@ -302,9 +305,8 @@ mod test {
         }
     }
 
-    fn test_oog(caller: &Account, callee: &Account, is_root: bool) {
-        let tx_gas = if is_root { 21100 } else { 25000 };
-        let ctx = TestContext::<3, 1>::new(
+    fn test_ctx(caller: &Account, callee: &Account, tx_gas: u64) -> TestContext<3, 1> {
+        TestContext::<3, 1>::new(
             None,
             |accs| {
                 accs[0]
@@ -329,9 +331,114 @@ mod test {
             },
             |block, _tx| block.number(0xcafeu64),
         )
-        .unwrap();
+        .unwrap()
+    }
+
+    fn test_oog(caller: &Account, callee: &Account, is_root: bool) {
+        let tx_gas = if is_root { 21100 } else { 25000 };
+        CircuitTestBuilder::new_from_test_ctx(test_ctx(caller, callee, tx_gas)).run();
+    }
+
+    /// Runs `opcode` in the root call with `gas_cost - 1` gas left, so that it
+    /// runs out of gas by a single unit.
+    fn test_oog_by_one(opcode: OpcodeId, stack: Stack, callee: &Account, gas_cost: u64) {
+        // `call_bytecode` pushes all the call arguments with PUSH32
+        let num_pushes = if opcode == OpcodeId::CALL || opcode == OpcodeId::CALLCODE {
+            7
+        } else {
+            6
+        };
+        let gas_left = gas_cost - 1;
+        let tx_gas = GasCost::TX.0 + num_pushes * OpcodeId::PUSH32.constant_gas_cost().0 + gas_left;
+
+        CircuitTestBuilder::new_from_test_ctx(test_ctx(&caller(opcode, stack), callee, tx_gas))
+            .block_modifier(Box::new(move |block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::ErrorOutOfGasCall)
+                    .unwrap();
+                assert_eq!(step.gas_left, gas_left);
+            }))
+            .run();
+    }
+
+    #[test]
+    fn test_oog_call_cold_access() {
+        let callee = callee(bytecode! { STOP });
+        for opcode in TEST_CALL_OPCODES {
+            test_oog_by_one(
+                *opcode,
+                Stack::default(),
+                &callee,
+                GasCost::COLD_ACCOUNT_ACCESS.0,
+            );
+        }
+    }
+
+    #[test]
+    fn test_oog_call_value_transfer() {
+        let stack = Stack {
+            value: 1.into(),
+            ..Default::default()
+        };
+        // The callee exists, so only the value transfer cost is added.
+        let callee = callee(bytecode! { STOP });
+        for opcode in [OpcodeId::CALL, OpcodeId::CALLCODE] {
+            test_oog_by_one(
+                opcode,
+                stack,
+                &callee,
+                GasCost::COLD_ACCOUNT_ACCESS.0 + GasCost::CALL_WITH_VALUE.0,
+            );
+        }
+    }
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    #[test]
+    fn test_oog_call_new_account() {
+        let stack = Stack {
+            value: 1.into(),
+            ..Default::default()
+        };
+        let empty_callee = callee(Bytecode::default());
+        test_oog_by_one(
+            OpcodeId::CALL,
+            stack,
+            &empty_callee,
+            GasCost::COLD_ACCOUNT_ACCESS.0 + GasCost::CALL_WITH_VALUE.0 + GasCost::NEW_ACCOUNT.0,
+        );
+        // CALLCODE never creates the callee account.
+        test_oog_by_one(
+            OpcodeId::CALLCODE,
+            stack,
+            &empty_callee,
+            GasCost::COLD_ACCOUNT_ACCESS.0 + GasCost::CALL_WITH_VALUE.0,
+        );
+        // Without value the callee account isn't created either.
+        test_oog_by_one(
+            OpcodeId::CALL,
+            Stack::default(),
+            &empty_callee,
+            GasCost::COLD_ACCOUNT_ACCESS.0,
+        );
+    }
+
+    #[test]
+    fn test_oog_call_memory_expansion() {
+        let stack = Stack {
+            rd_offset: 0,
+            rd_length: 0x400,
+            ..Default::default()
+        };
+        let callee = callee(bytecode! { STOP });
+        for opcode in TEST_CALL_OPCODES {
+            test_oog_by_one(
+                *opcode,
+                stack,
+                &callee,
+                GasCost::COLD_ACCOUNT_ACCESS.0 + memory_expansion_gas_cost(0, 0x400 / 32),
+            );
+        }
     }
 
     #[test]
@@ -6,7 +6,7 @@ use crate::{
         util::{
             common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::{BinaryNumberGadget, ConstantDivisionGadget, LtGadget},
+            math_gadget::{BinaryNumberGadget, ConstantDivmodGadget, LtGadget},
             CachedRegion, Cell,
         },
     },
@@ -24,8 +24,8 @@ pub(crate) struct ErrorOOGPrecompileGadget<F> {
     addr_bits: BinaryNumberGadget<F, 4>,
     call_data_length: Cell<F>,
     is_root: Cell<F>,
-    n_pairs: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
-    n_words: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
+    n_pairs: ConstantDivmodGadget<F, N_BYTES_MEMORY_WORD_SIZE, { N_BYTES_EC_PAIR as u64 }>,
+    n_words: ConstantDivmodGadget<F, N_BYTES_MEMORY_WORD_SIZE, { N_BYTES_WORD as u64 }>,
     required_gas: Cell<F>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     restore_context: RestoreContextGadget<F>,
@@ -50,21 +50,19 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
 
         let n_pairs = cb.condition(
             addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
+            |cb| ConstantDivmodGadget::construct(cb, call_data_length.expr()),
+        );
+        // Both identity and sha256 charge per input word.
+        let n_words = cb.condition(
+            addr_bits.value_equals(PrecompileCalls::Identity)
+                + addr_bits.value_equals(PrecompileCalls::Sha256),
             |cb| {
-                ConstantDivisionGadget::construct(
+                ConstantDivmodGadget::construct(
                     cb,
-                    call_data_length.expr(),
-                    N_BYTES_EC_PAIR as u64,
+                    call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
                 )
             },
         );
-        let n_words = cb.condition(addr_bits.value_equals(PrecompileCalls::Identity), |cb| {
-            ConstantDivisionGadget::construct(
-                cb,
-                call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
-                N_BYTES_WORD as u64,
-            )
-        });
 
         // calculate required gas for precompile
         let precompiles_required_gas = vec![
@@ -72,7 +70,6 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
                 addr_bits.value_equals(PrecompileCalls::Ecrecover),
                 GasCost::PRECOMPILE_ECRECOVER_BASE.expr(),
             ),
-            // These are handled in PrecompileFailedGadget
             (
                 addr_bits.value_equals(PrecompileCalls::Sha256),
                 GasCost::PRECOMPILE_SHA256_BASE.expr()
@@ -268,6 +265,70 @@ mod test {
                 .to_word(),
                 ..Default::default()
             },
+            PrecompileCallArgs {
+                name: "identity single word",
+                setup_code: bytecode! {
+                    PUSH1(0xff)
+                    PUSH1(0x00)
+                    MSTORE
+                },
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x20.into(),
+                ret_offset: 0x20.into(),
+                ret_size: 0x20.into(),
+                address: PrecompileCalls::Identity.address().to_word(),
+                gas: (PrecompileCalls::Identity.base_gas_cost().as_u64()
+                    + GasCost::PRECOMPILE_IDENTITY_PER_WORD.as_u64()
+                    - 1)
+                .to_word(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                name: "ecrecover",
+                setup_code: bytecode! {
+                    // msg hash from 0x00
+                    PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                    PUSH1(0x00)
+                    MSTORE
+                    // signature v from 0x20
+                    PUSH1(28)
+                    PUSH1(0x20)
+                    MSTORE
+                    // signature r from 0x40
+                    PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                    PUSH1(0x40)
+                    MSTORE
+                    // signature s from 0x60
+                    PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                    PUSH1(0x60)
+                    MSTORE
+                },
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x80.into(),
+                ret_offset: 0x80.into(),
+                ret_size: 0x20.into(),
+                address: PrecompileCalls::Ecrecover.address().to_word(),
+                gas: (GasCost::PRECOMPILE_ECRECOVER_BASE.as_u64() - 1).to_word(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                name: "sha256 multiple words",
+                setup_code: bytecode! {
+                    PUSH32(word!("0x0123456789abcdef0f1e2d3c4b5a6978aabbccdd001122331039abcdefefef84"))
+                    PUSH1(0x00)
+                    MSTORE
+                },
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x21.into(),
+                ret_offset: 0x40.into(),
+                ret_size: 0x20.into(),
+                address: PrecompileCalls::Sha256.address().to_word(),
+                gas: (GasCost::PRECOMPILE_SHA256_BASE.as_u64()
+                    + 2 * GasCost::PRECOMPILE_SHA256_PER_WORD.as_u64()
+                    - 1)
+                .to_word(),
+                ..Default::default()
+            },
             PrecompileCallArgs {
                 name: "modexp length in u256",
                 setup_code: bytecode! {
@@ -202,7 +202,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{evm_circuit::step::ExecutionState, test_util::CircuitTestBuilder};
     use eth_types::{bytecode, word, Bytecode, ToWord, U256};
     use mock::{
         eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
@@ -233,6 +233,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_static_memory_end_offset_overflows_u64() {
+        // `offset + 32` is `2^64` and `2^64 + 1`, which must be treated as
+        // unaffordable rather than wrapping around to a small end offset.
+        for offset in [u64::MAX - 31, u64::MAX - 30] {
+            for code in testing_bytecodes(offset.into()).iter() {
+                test_root(code);
+                test_internal(code);
+            }
+        }
+    }
+
     #[test]
     fn test_oog_static_memory_max_word_address() {
         for code in testing_bytecodes(U256::MAX).iter() {
@@ -276,7 +288,12 @@ mod tests {
         )
         .unwrap();
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                assert!(block.txs[0].steps.iter().any(|step| step.execution_state
+                    == ExecutionState::ErrorOutOfGasStaticMemoryExpansion));
+            }))
+            .run();
     }
 
     fn test_internal(code: &Bytecode) {
@@ -223,9 +223,13 @@ impl<F: Field> ExecutionGadget<F> for ExponentiationGadget<F> {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, Word};
-    use mock::TestContext;
+    use crate::{evm_circuit::step::ExecutionState, test_util::CircuitTestBuilder};
+    use eth_types::{
+        bytecode,
+        evm_types::{GasCost, OpcodeId},
+        Word,
+    };
+    use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
 
     fn test_ok(base: Word, exponent: Word) {
         let code = bytecode! {
@@ -269,4 +273,73 @@ mod tests {
         test_ok(Word::MAX, 2.into());
         test_ok(Word::MAX, 3.into());
     }
+
+    #[test]
+    fn exp_gadget_dynamic_gas() {
+        // Exponents of 0, 1 and 32 bytes.
+        for (exponent, byte_size) in [(Word::zero(), 0), (0xffu64.into(), 1), (Word::MAX, 32)] {
+            let code = bytecode! {
+                PUSH32(exponent)
+                PUSH32(3)
+                EXP
+                STOP
+            };
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+            )
+            .block_modifier(Box::new(move |block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::EXP)
+                    .unwrap();
+                assert_eq!(
+                    step.gas_cost,
+                    OpcodeId::EXP.constant_gas_cost().0 + byte_size * GasCost::EXP_BYTE_TIMES.0
+                );
+            }))
+            .run();
+        }
+    }
+
+    #[test]
+    fn exp_gadget_out_of_gas() {
+        // One gas short of the dynamic cost for exponents of 0, 1 and 32 bytes.
+        for (exponent, byte_size) in [(Word::zero(), 0), (0xffu64.into(), 1), (Word::MAX, 32)] {
+            let code = bytecode! {
+                PUSH32(exponent)
+                PUSH32(3)
+                EXP
+                STOP
+            };
+            let gas = GasCost::TX.0
+                + 2 * OpcodeId::PUSH32.constant_gas_cost().0
+                + OpcodeId::EXP.constant_gas_cost().0
+                + byte_size * GasCost::EXP_BYTE_TIMES.0
+                - 1;
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(code),
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[1].address)
+                        .to(accs[0].address)
+                        .gas(gas.into());
+                },
+                |block, _tx| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx)
+                .block_modifier(Box::new(|block| {
+                    let step = block.txs[0]
+                        .steps
+                        .iter()
+                        .find(|step| step.opcode == Some(OpcodeId::EXP))
+                        .unwrap();
+                    assert_eq!(step.execution_state, ExecutionState::ErrorOutOfGasEXP);
+                }))
+                .run();
+        }
+    }
 }
@@ -163,8 +163,11 @@ impl<F: Field> ExecutionGadget<F> for ExtcodesizeGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, geth_types::Account, Bytecode, ToWord};
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_bytes},
+        test_util::CircuitTestBuilder,
+    };
+    use eth_types::{bytecode, geth_types::Account, Bytecode, ToWord, Word};
     use mock::{
         generate_mock_call_bytecode, MockCallBytecodeParams, TestContext, MOCK_1_ETH,
         MOCK_ACCOUNTS, MOCK_CODES,
@@ -195,6 +198,22 @@ mod test {
         test_ok(&account, true);
     }
 
+    #[test]
+    fn test_extcodesize_gadget_empty_code() {
+        // Existing account without code, whose code hash is the empty code hash.
+        let account = Account {
+            address: MOCK_ACCOUNTS[4],
+            balance: *MOCK_1_ETH,
+            ..Default::default()
+        };
+        assert!(!account.is_empty());
+
+        // Test for cold account.
+        test_ok(&account, false);
+        // Test for warm account.
+        test_ok(&account, true);
+    }
+
     #[test]
     fn test_extcodesize_gadget_with_long_code() {
         let account = Account {
@@ -245,7 +264,10 @@ mod test {
                 accs[1].address(addr_a).code(code_a);
                 // Set code if account exists.
                 if account_exists {
-                    accs[2].address(account.address).code(account.code.clone());
+                    accs[2]
+                        .address(account.address)
+                        .code(account.code.clone())
+                        .balance(account.balance);
                 } else {
                     accs[2].address(mock::MOCK_ACCOUNTS[2]).balance(*MOCK_1_ETH);
                 }
@@ -258,6 +280,21 @@ mod test {
         )
         .unwrap();
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        let code_size = Word::from(account.code.len());
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                for step in block.txs[0]
+                    .steps
+                    .iter()
+                    .filter(|step| step.execution_state == ExecutionState::EXTCODESIZE)
+                {
+                    // Only non-existing accounts have a zero code hash.
+                    let code_hash = block.rws[step.rw_indices[5]].account_value_pair().0;
+                    assert_eq!(code_hash.is_zero(), !account_exists);
+                    let pushed = block.rws[*step.rw_indices.last().unwrap()].stack_value();
+                    assert_eq!(pushed, code_size);
+                }
+            }))
+            .run();
     }
 }
@@ -1,27 +1,26 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::N_BYTES_PROGRAM_COUNTER,
         step::ExecutionState,
         util::{
-            common_gadget::SameContextGadget,
+            common_gadget::{JumpDestValidGadget, SameContextGadget},
             constraint_builder::{
-                EVMConstraintBuilder, StepStateTransition,
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
                 Transition::{Delta, To},
             },
-            from_bytes, CachedRegion, RandomLinearCombination,
+            CachedRegion,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
     util::Expr,
 };
-use eth_types::{evm_types::OpcodeId, Field, ToLittleEndian};
+use eth_types::{evm_types::OpcodeId, Field};
 use halo2_proofs::plonk::Error;
 
 #[derive(Clone, Debug)]
 pub(crate) struct JumpGadget<F> {
     same_context: SameContextGadget<F>,
-    destination: RandomLinearCombination<F, N_BYTES_PROGRAM_COUNTER>,
+    dest: JumpDestValidGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for JumpGadget<F> {
@@ -30,15 +29,15 @@ impl<F: Field> ExecutionGadget<F> for JumpGadget<F> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::JUMP;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let destination = cb.query_word_rlc();
+        let dest = JumpDestValidGadget::construct(cb);
 
         // Pop the value from the stack
-        cb.stack_pop(destination.expr());
+        cb.stack_pop(dest.original_word());
 
-        // Lookup opcode at destination
-        cb.opcode_lookup_at(
-            from_bytes::expr(&destination.cells),
-            OpcodeId::JUMPDEST.expr(),
+        // The opcode at destination must be JUMPDEST
+        cb.require_equal(
+            "JUMP destination must be a valid JUMPDEST",
+            dest.is_valid(),
             1.expr(),
         );
 
@@ -46,17 +45,14 @@ impl<F: Field> ExecutionGadget<F> for JumpGadget<F> {
         let opcode = cb.query_cell();
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(1.expr()),
-            program_counter: To(from_bytes::expr(&destination.cells)),
+            program_counter: To(dest.valid_value()),
             stack_pointer: Delta(1.expr()),
             gas_left: Delta(-OpcodeId::JUMP.constant_gas_cost().expr()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
 
-        Self {
-            same_context,
-            destination,
-        }
+        Self { same_context, dest }
     }
 
     fn assign_exec_step(
@@ -65,21 +61,13 @@ impl<F: Field> ExecutionGadget<F> for JumpGadget<F> {
         offset: usize,
         block: &Block<F>,
         _: &Transaction,
-        _: &Call,
+        call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
         self.same_context.assign_exec_step(region, offset, step)?;
 
         let destination = block.rws[step.rw_indices[0]].stack_value();
-        self.destination.assign(
-            region,
-            offset,
-            Some(
-                destination.to_le_bytes()[..N_BYTES_PROGRAM_COUNTER]
-                    .try_into()
-                    .unwrap(),
-            ),
-        )?;
+        self.dest.assign(region, offset, block, call, destination)?;
 
         Ok(())
     }
@@ -1,10 +1,9 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::N_BYTES_PROGRAM_COUNTER,
         step::ExecutionState,
         util::{
-            common_gadget::{SameContextGadget, WordByteRangeGadget},
+            common_gadget::{JumpDestValidGadget, SameContextGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
                 Transition::{Delta, To},
@@ -22,7 +21,7 @@ use halo2_proofs::plonk::Error;
 #[derive(Clone, Debug)]
 pub(crate) struct JumpiGadget<F> {
     same_context: SameContextGadget<F>,
-    dest: WordByteRangeGadget<F, N_BYTES_PROGRAM_COUNTER>,
+    dest: JumpDestValidGadget<F>,
     phase2_condition: Cell<F>,
     is_condition_zero: IsZeroGadget<F>,
 }
@@ -33,7 +32,7 @@ impl<F: Field> ExecutionGadget<F> for JumpiGadget<F> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::JUMPI;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let dest = WordByteRangeGadget::construct(cb);
+        let dest = JumpDestValidGadget::construct(cb);
         let phase2_condition = cb.query_cell_phase2();
 
         // Pop the value from the stack
@@ -44,15 +43,13 @@ impl<F: Field> ExecutionGadget<F> for JumpiGadget<F> {
         let is_condition_zero = IsZeroGadget::construct(cb, phase2_condition.expr());
         let should_jump = 1.expr() - is_condition_zero.expr();
 
-        // Lookup opcode at destination when should_jump
+        // The opcode at destination must be JUMPDEST when should_jump
         cb.condition(should_jump.clone(), |cb| {
             cb.require_equal(
-                "JUMPI destination must be within range if condition is non-zero",
-                dest.not_overflow(),
+                "JUMPI destination must be a valid JUMPDEST if condition is non-zero",
+                dest.is_valid(),
                 1.expr(),
             );
-
-            cb.opcode_lookup_at(dest.valid_value(), OpcodeId::JUMPDEST.expr(), 1.expr());
         });
 
         // Transit program_counter to destination when should_jump, otherwise by
@@ -88,7 +85,7 @@ impl<F: Field> ExecutionGadget<F> for JumpiGadget<F> {
         offset: usize,
         block: &Block<F>,
         _: &Transaction,
-        _: &Call,
+        call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
         self.same_context.assign_exec_step(region, offset, step)?;
@@ -97,7 +94,7 @@ impl<F: Field> ExecutionGadget<F> for JumpiGadget<F> {
             [step.rw_indices[0], step.rw_indices[1]].map(|idx| block.rws[idx].stack_value());
         let condition = region.word_rlc(condition);
 
-        self.dest.assign(region, offset, destination)?;
+        self.dest.assign(region, offset, block, call, destination)?;
         self.phase2_condition.assign(region, offset, condition)?;
         self.is_condition_zero
             .assign_value(region, offset, condition)?;
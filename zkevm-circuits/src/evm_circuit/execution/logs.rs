@@ -283,8 +283,16 @@ impl<F: Field> ExecutionGadget<F> for LogGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::{evm_types::OpcodeId, Bytecode, Word};
+    use crate::{
+        evm_circuit::step::ExecutionState,
+        table::{RwTableTag, TxLogFieldTag},
+        test_util::CircuitTestBuilder,
+        witness::Rw,
+    };
+    use eth_types::{
+        evm_types::{gas_utils::memory_expansion_gas_cost, GasCost, OpcodeId},
+        Bytecode, ToBigEndian, Word,
+    };
     use mock::TestContext;
     use rand::Rng;
 
@@ -379,6 +387,75 @@ mod test {
         );
     }
 
+    #[test]
+    fn log_gadget_data() {
+        // within the written memory, from an unaligned offset
+        test_log_data(0x50, 0x20);
+        // zero length
+        test_log_data(0x40, 0);
+        // past the written memory, which expands it
+        test_log_data(0x70, 0x40);
+    }
+
+    // LOG0 the memory `[mstart, mstart + msize)`, after writing 64 random bytes
+    // at 0x40, and check the data written to the log and the gas charged.
+    fn test_log_data(mstart: usize, msize: usize) {
+        let mut pushdata = [0u8; 64];
+        rand::thread_rng().try_fill(&mut pushdata[..]).unwrap();
+        let mut code = prepare_code(&pushdata, 0x40);
+        code.push(32, Word::from(msize));
+        code.push(32, Word::from(mstart));
+        code.write_op(OpcodeId::LOG0);
+        code.op_stop();
+
+        let mut memory = vec![0u8; 0x40];
+        memory.extend_from_slice(&pushdata);
+        memory.resize(memory.len().max(mstart + msize), 0);
+        let expected_data = memory[mstart..mstart + msize].to_vec();
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        )
+        .block_modifier(Box::new(move |block| {
+            let data = block.rws.0[&RwTableTag::TxLog]
+                .iter()
+                .filter_map(|rw| match rw {
+                    Rw::TxLog {
+                        field_tag: TxLogFieldTag::Data,
+                        index,
+                        value,
+                        ..
+                    } => Some((*index, value.to_be_bytes())),
+                    _ => None,
+                })
+                .fold(Vec::new(), |mut data, (index, word)| {
+                    assert_eq!(index, data.len());
+                    data.extend_from_slice(&word);
+                    data
+                });
+            assert_eq!(data[..msize.min(data.len())], expected_data[..]);
+
+            let step = block.txs[0]
+                .steps
+                .iter()
+                .find(|step| step.execution_state == ExecutionState::LOG)
+                .unwrap();
+            let curr_memory_word_size = step.memory_word_size();
+            let next_memory_word_size = if msize == 0 {
+                curr_memory_word_size
+            } else {
+                curr_memory_word_size.max((mstart + msize).div_ceil(32) as u64)
+            };
+            assert_eq!(
+                step.gas_cost,
+                GasCost::LOG.0
+                    + 8 * msize as u64
+                    + memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size)
+            );
+        }))
+        .run();
+    }
+
     #[derive(Clone, Copy)]
     struct Stack {
         mstart: Word,
@@ -153,7 +153,12 @@ impl<F: Field> ExecutionGadget<F> for MulDivModGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_word},
+        table::RwTableTag,
+        test_util::{assert_gadget_rejects, CircuitTestBuilder},
+        witness::Rw,
+    };
     use eth_types::{bytecode, evm_types::OpcodeId, Word};
     use mock::TestContext;
 
@@ -208,6 +213,50 @@ mod test {
         );
     }
 
+    #[test]
+    fn div_gadget_power_of_two_divisor() {
+        for dividend in [Word::from(0xABCDEF), Word::one() << 255, Word::MAX] {
+            for divisor in [
+                Word::zero(),
+                Word::one(),
+                Word::from(2),
+                Word::one() << 128,
+                Word::one() << 255,
+            ] {
+                test_ok(OpcodeId::DIV, dividend, divisor);
+            }
+        }
+    }
+
+    #[test]
+    fn div_gadget_power_of_two_divisor_wrong_quotient() {
+        // Claim `(2^256 - 1) / 2^255 == 0`, leaving a residue of `2^256 - 1`
+        // which is not below the divisor.
+        let bytecode = bytecode! {
+            PUSH32(Word::one() << 255)
+            PUSH32(Word::MAX)
+            DIV
+            STOP
+        };
+        assert_gadget_rejects(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            Box::new(|block| {
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::MUL_DIV_MOD)
+                    .unwrap();
+                let (_, push_idx) = step.rw_indices[2];
+                if let Rw::Stack { value, .. } =
+                    &mut block.rws.0.get_mut(&RwTableTag::Stack).unwrap()[push_idx]
+                {
+                    assert_eq!(*value, Word::one());
+                    *value = Word::zero();
+                }
+            }),
+        );
+    }
+
     #[test]
     fn div_gadget_rand() {
         let dividend = rand_word();
@@ -97,9 +97,12 @@ impl<F: Field> ExecutionGadget<F> for OriginGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
-    use mock::TestContext;
+    use crate::{
+        evm_circuit::step::ExecutionState,
+        test_util::{subcall_ctx, CircuitTestBuilder},
+    };
+    use eth_types::{bytecode, evm_types::OpcodeId, ToWord, Word};
+    use mock::{TestContext, MOCK_ACCOUNTS};
 
     #[test]
     fn origin_gadget_test() {
@@ -113,4 +116,34 @@ mod test {
         )
         .run();
     }
+
+    #[test]
+    fn origin_gadget_nested_call() {
+        let ctx = subcall_ctx(
+            OpcodeId::CALL,
+            Word::zero(),
+            Word::zero(),
+            bytecode! {
+                ORIGIN
+                STOP
+            },
+        );
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                let tx = &block.txs[0];
+                let step = tx
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::ORIGIN)
+                    .unwrap();
+                // ORIGIN runs in the inner call, whose caller is the contract
+                assert_eq!(tx.calls[step.call_index].caller_address, MOCK_ACCOUNTS[0]);
+                assert_eq!(
+                    block.rws[step.rw_indices[1]].stack_value(),
+                    MOCK_ACCOUNTS[2].to_word()
+                );
+            }))
+            .run();
+    }
 }
@@ -441,6 +441,39 @@ mod test {
                 address: PrecompileCalls::Bn128Add.address().to_word(),
                 ..Default::default()
             },
+            PrecompileCallArgs {
+                name: "ecAdd (valid inputs: P is the point at infinity)",
+                // P = (0, 0)
+                // Q = (1, 2)
+                setup_code: bytecode! {
+                        // q_x = 1
+                        PUSH1(0x01)
+                        PUSH1(0x40)
+                        MSTORE
+                        // q_y = 2
+                        PUSH1(0x02)
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x80.into(),
+                ret_offset: 0x80.into(),
+                ret_size: 0x40.into(),
+                address: PrecompileCalls::Bn128Add.address().to_word(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                name: "ecAdd (valid inputs: P and Q are the point at infinity)",
+                // P = (0, 0)
+                // Q = (0, 0)
+                setup_code: bytecode! {},
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x80.into(),
+                ret_offset: 0x80.into(),
+                ret_size: 0x40.into(),
+                address: PrecompileCalls::Bn128Add.address().to_word(),
+                ..Default::default()
+            },
             PrecompileCallArgs {
                 name: "ecAdd (should succeed on empty inputs)",
                 setup_code: bytecode! {},
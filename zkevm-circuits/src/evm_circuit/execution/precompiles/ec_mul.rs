@@ -534,6 +534,55 @@ mod test {
                 address: PrecompileCalls::Bn128Mul.address().to_word(),
                 ..Default::default()
             },
+            PrecompileCallArgs {
+                name: "ecMul (valid input: s == 0 yields the point at infinity)",
+                // P = (1, 2)
+                // s = 0
+                setup_code: bytecode! {
+                        // p_x
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+
+                        // p_y
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                    },
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x60.into(),
+                ret_offset: 0x60.into(),
+                ret_size: 0x40.into(),
+                address: PrecompileCalls::Bn128Mul.address().to_word(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                name: "ecMul (valid input: s == 1 returns P)",
+                // P = (1, 2)
+                // s = 1
+                setup_code: bytecode! {
+                        // p_x
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+
+                        // p_y
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+
+                        // s
+                        PUSH1(0x01)
+                        PUSH1(0x40)
+                        MSTORE
+                    },
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x60.into(),
+                ret_offset: 0x60.into(),
+                ret_size: 0x40.into(),
+                address: PrecompileCalls::Bn128Mul.address().to_word(),
+                ..Default::default()
+            },
             PrecompileCallArgs {
                 name: "ecMul (invalid input: point not on curve)",
                 // P = (2, 3)
@@ -11,7 +11,7 @@ use crate::{
         util::{
             common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::ConstantDivisionGadget,
+            math_gadget::ConstantDivmodGadget,
             rlc, CachedRegion, Cell,
         },
     },
@@ -25,7 +25,7 @@ pub struct IdentityGadget<F> {
     output_bytes_rlc: Cell<F>,
     return_bytes_rlc: Cell<F>,
 
-    input_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
+    input_word_size: ConstantDivmodGadget<F, N_BYTES_MEMORY_WORD_SIZE, { N_BYTES_WORD as u64 }>,
     is_success: Cell<F>,
     callee_address: Cell<F>,
     is_root: Cell<F>,
@@ -59,10 +59,9 @@ impl<F: Field> ExecutionGadget<F> for IdentityGadget<F> {
             ]
             .map(|tag| cb.call_context(None, tag));
 
-        let input_word_size = ConstantDivisionGadget::construct(
+        let input_word_size = ConstantDivmodGadget::construct(
             cb,
             call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
-            N_BYTES_WORD as u64,
         );
 
         let gas_cost = select::expr(
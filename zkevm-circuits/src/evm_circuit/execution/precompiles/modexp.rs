@@ -18,7 +18,7 @@ use crate::{
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{
                 BinaryNumberGadget, BitLengthGadget, ByteOrWord, ByteSizeGadget,
-                ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
+                ConstantDivmodGadget, IsZeroGadget, LtGadget, MinMaxGadget,
             },
             padding_gadget::PaddingGadget,
             rlc, CachedRegion, Cell,
@@ -554,12 +554,12 @@ impl<F: Field> Limbs<F> {
 #[derive(Clone, Debug)]
 pub(crate) struct ModExpGasCost<F> {
     max_length: MinMaxGadget<F, 1>,
-    words: ConstantDivisionGadget<F, 1>,
+    words: ConstantDivmodGadget<F, 1, 8>,
     exp_is_zero: IsZeroGadget<F>,
     exp_byte_size: ByteSizeGadget<F>,
     exp_msb_bit_length: BitLengthGadget<F>,
     exp_msb: BinaryNumberGadget<F, N_BITS_U8>,
-    calc_gas: ConstantDivisionGadget<F, N_BYTES_U64>,
+    calc_gas: ConstantDivmodGadget<F, N_BYTES_U64, 3>,
     dynamic_gas: MinMaxGadget<F, N_BYTES_U64>,
 }
 
@@ -571,7 +571,7 @@ impl<F: Field> ModExpGasCost<F> {
         m_size: &SizeRepresent<F>,
     ) -> Self {
         let max_length = MinMaxGadget::construct(cb, b_size.value(), m_size.value());
-        let words = ConstantDivisionGadget::construct(cb, max_length.max() + 7.expr(), 8);
+        let words = ConstantDivmodGadget::construct(cb, max_length.max() + 7.expr());
         let multiplication_complexity = words.quotient() * words.quotient();
         let exp_is_zero = IsZeroGadget::construct(
             cb,
@@ -615,7 +615,7 @@ impl<F: Field> ModExpGasCost<F> {
             exp_bit_length.expr() - 1.expr(),
         );
         let calc_gas =
-            ConstantDivisionGadget::construct(cb, multiplication_complexity * iteration_count, 3);
+            ConstantDivmodGadget::construct(cb, multiplication_complexity * iteration_count);
         let dynamic_gas = MinMaxGadget::construct(
             cb,
             GasCost::PRECOMPILE_MODEXP_MIN.expr(),
@@ -10,7 +10,7 @@ use crate::{
         step::ExecutionState,
         util::{
             common_gadget::RestoreContextGadget, constraint_builder::EVMConstraintBuilder,
-            math_gadget::ConstantDivisionGadget, rlc, CachedRegion, Cell,
+            math_gadget::ConstantDivmodGadget, rlc, CachedRegion, Cell,
         },
     },
     table::CallContextFieldTag,
@@ -23,7 +23,7 @@ pub struct SHA256Gadget<F> {
     output_bytes_rlc: Cell<F>,
     return_bytes_rlc: Cell<F>,
 
-    input_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
+    input_word_size: ConstantDivmodGadget<F, N_BYTES_MEMORY_WORD_SIZE, { N_BYTES_WORD as u64 }>,
     is_success: Cell<F>,
     callee_address: Cell<F>,
     is_root: Cell<F>,
@@ -57,10 +57,9 @@ impl<F: Field> ExecutionGadget<F> for SHA256Gadget<F> {
             ]
             .map(|tag| cb.call_context(None, tag));
 
-        let input_word_size = ConstantDivisionGadget::construct(
+        let input_word_size = ConstantDivmodGadget::construct(
             cb,
             call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
-            N_BYTES_WORD as u64,
         );
 
         let gas_cost = select::expr(
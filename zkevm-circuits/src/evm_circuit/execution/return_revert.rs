@@ -501,7 +501,12 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{
+        evm_circuit::{step::ExecutionState, util::code_deposit_cost},
+        table::RwTableTag,
+        test_util::CircuitTestBuilder,
+        witness::Rw,
+    };
     use eth_types::{
         address, bytecode, evm_types::OpcodeId, geth_types::Account, Address, Bytecode, ToWord,
         Word,
@@ -659,6 +664,110 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_return_nonroot_noncreate_return_data_and_reversion() {
+        // (callee memory offset, callee length): zero length, in-bounds and
+        // memory-expanding RETURN/REVERT.
+        let test_parameters = [(0, 0), (0, 0x20), (0x40, 0x20)];
+        for ((offset, length), is_return) in
+            test_parameters.iter().cartesian_product(&[true, false])
+        {
+            let (offset, length, is_return) = (*offset, *length, *is_return);
+            let mut callee_code = bytecode! {
+                // Reversible write which must be rolled back by REVERT.
+                PUSH1(0x01)
+                PUSH1(0x00)
+                SSTORE
+                PUSH1(length)
+                PUSH1(offset)
+            };
+            callee_code.write_op(if is_return {
+                OpcodeId::RETURN
+            } else {
+                OpcodeId::REVERT
+            });
+            let callee = Account {
+                address: CALLEE_ADDRESS,
+                code: callee_code.into(),
+                nonce: Word::one(),
+                ..Default::default()
+            };
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: bytecode! {
+                    PUSH1(0x20) // return data length
+                    PUSH1(0x00) // return data offset
+                    PUSH1(0x00) // call data length
+                    PUSH1(0x00) // call data offset
+                    PUSH1(0x00) // value
+                    PUSH32(CALLEE_ADDRESS.to_word())
+                    PUSH32(40000) // gas
+                    CALL
+                    RETURNDATASIZE
+                    STOP
+                }
+                .into(),
+                nonce: Word::one(),
+                ..Default::default()
+            };
+
+            let ctx = TestContext::<3, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x000000000000000000000000000000000000cafe"))
+                        .balance(Word::from(10u64.pow(19)));
+                    accs[1].account(&caller);
+                    accs[2].account(&callee);
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[0].address)
+                        .to(accs[1].address)
+                        .gas(100000u64.into());
+                },
+                |block, _tx| block.number(0xcafeu64),
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx)
+                .block_modifier(Box::new(move |block| {
+                    let tx = &block.txs[0];
+                    // The callee is persistent only when it halts with RETURN.
+                    assert_eq!(tx.calls[1].is_success, is_return);
+                    assert_eq!(tx.calls[1].is_persistent, is_return);
+                    // Both RETURN and REVERT set the caller's return data.
+                    let step = tx
+                        .steps
+                        .iter()
+                        .find(|step| step.execution_state == ExecutionState::RETURNDATASIZE)
+                        .unwrap();
+                    assert_eq!(
+                        block.rws[step.rw_indices[1]].stack_value(),
+                        Word::from(length)
+                    );
+                    // REVERT writes the slot back to its original value.
+                    let slot_writes = block.rws.0[&RwTableTag::AccountStorage]
+                        .iter()
+                        .filter(|rw| rw.is_write() && rw.address() == Some(CALLEE_ADDRESS))
+                        .sorted_by_key(|rw| rw.rw_counter())
+                        .map(|rw| match rw {
+                            Rw::AccountStorage {
+                                value, value_prev, ..
+                            } => (*value_prev, *value),
+                            _ => unreachable!(),
+                        })
+                        .collect_vec();
+                    let mut expected = vec![(Word::zero(), Word::one())];
+                    if !is_return {
+                        expected.push((Word::one(), Word::zero()));
+                    }
+                    assert_eq!(slot_writes, expected);
+                }))
+                .run();
+        }
+    }
+
     #[test]
     fn test_return_root_create() {
         let test_parameters = [(0, 0), (0, 10), (300, 20), (1000, 0)];
@@ -682,6 +791,40 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_return_root_create_code_deposit_cost() {
+        let length = 100;
+        let tx_input = callee_bytecode(true, 0, length).code();
+        let ctx = TestContext::<1, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[0].address).input(tx_input.into());
+            },
+            |block, _| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                let steps = &block.txs[0].steps;
+                let idx = steps
+                    .iter()
+                    .position(|step| step.execution_state == ExecutionState::RETURN_REVERT)
+                    .unwrap();
+                let (step, next_step) = (&steps[idx], &steps[idx + 1]);
+                assert_eq!(next_step.execution_state, ExecutionState::EndTx);
+                // gas_cost only covers memory expansion, the rest of the drop is the deposit.
+                assert_eq!(
+                    step.gas_left - step.gas_cost - next_step.gas_left,
+                    code_deposit_cost(length as usize)
+                );
+            }))
+            .run();
+    }
+
     #[test]
     fn test_return_root_create_edge() {
         let test_parameters = [(0, 0), (0, 10), (300, 20), (1000, 0)];
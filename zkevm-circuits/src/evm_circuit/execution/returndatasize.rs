@@ -86,9 +86,15 @@ impl<F: Field> ExecutionGadget<F> for ReturnDataSizeGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, Word};
-    use mock::{generate_mock_call_bytecode, test_ctx::TestContext, MockCallBytecodeParams};
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_bytes},
+        test_util::CircuitTestBuilder,
+    };
+    use eth_types::{bytecode, Address, Bytecode, Word};
+    use itertools::Itertools;
+    use mock::{
+        generate_mock_call_bytecode, test_ctx::TestContext, MockCallBytecodeParams, MOCK_ACCOUNTS,
+    };
 
     fn test_ok_internal(return_data_offset: usize, return_data_size: usize) {
         let (addr_a, addr_b) = (mock::MOCK_ACCOUNTS[0], mock::MOCK_ACCOUNTS[1]);
@@ -155,4 +161,93 @@ mod test {
         )
         .run();
     }
+
+    /// Calls `address` without arguments, then pushes RETURNDATASIZE.
+    fn call_then_size(address: Address) -> Bytecode {
+        bytecode! {
+            .op_call(0x10000, address, 0, 0, 0, 0, 0)
+            POP
+            RETURNDATASIZE
+            POP
+        }
+    }
+
+    /// Runs `code` with `callees` deployed at `MOCK_ACCOUNTS[1]` and
+    /// `MOCK_ACCOUNTS[2]`, and checks the values pushed by the RETURNDATASIZE
+    /// steps, in order.
+    fn test_sizes(code: Bytecode, callees: [Bytecode; 2], expected: Vec<u64>) {
+        let [code_b, code_c] = callees;
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).code(code_b);
+                accs[2].address(MOCK_ACCOUNTS[2]).code(code_c);
+                accs[3]
+                    .address(MOCK_ACCOUNTS[3])
+                    .balance(Word::from(1u64 << 30));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[3].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                let sizes = block.txs[0]
+                    .steps
+                    .iter()
+                    .filter(|step| step.execution_state == ExecutionState::RETURNDATASIZE)
+                    .map(|step| block.rws[step.rw_indices[1]].stack_value())
+                    .collect_vec();
+                assert_eq!(
+                    sizes,
+                    expected.iter().copied().map(Word::from).collect_vec()
+                );
+            }))
+            .run();
+    }
+
+    #[test]
+    fn returndatasize_fresh_frame() {
+        let code = bytecode! {
+            RETURNDATASIZE
+            STOP
+        };
+        test_sizes(code, [bytecode! { STOP }, bytecode! { STOP }], vec![0]);
+    }
+
+    #[test]
+    fn returndatasize_after_stop() {
+        let mut code = call_then_size(MOCK_ACCOUNTS[1]);
+        code.op_stop();
+        test_sizes(code, [bytecode! { STOP }, bytecode! { STOP }], vec![0]);
+    }
+
+    #[test]
+    fn returndatasize_after_return() {
+        for size in [0x05, 0x20, 0x45] {
+            let mut code = call_then_size(MOCK_ACCOUNTS[1]);
+            code.op_stop();
+            let callee = bytecode! {
+                .op_return(0, size)
+            };
+            test_sizes(code, [callee, bytecode! { STOP }], vec![size]);
+        }
+    }
+
+    #[test]
+    fn returndatasize_reset_by_stop() {
+        // The size of the first call's return data must not leak past a
+        // second call that STOPs.
+        let mut code = call_then_size(MOCK_ACCOUNTS[1]);
+        code.append(&call_then_size(MOCK_ACCOUNTS[2]));
+        code.op_stop();
+        let callee = bytecode! {
+            .op_return(0, 0x20)
+        };
+        test_sizes(code, [callee, bytecode! { STOP }], vec![0x20, 0]);
+    }
 }
@@ -5,14 +5,14 @@ use crate::{
         step::ExecutionState,
         table::{FixedTableTag, Lookup},
         util::{
-            common_gadget::SameContextGadget,
+            common_gadget::{SameContextGadget, ShiftAmountGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
                 Transition::Delta,
             },
             from_bytes,
             math_gadget::{IsEqualGadget, IsZeroGadget, LtGadget},
-            select, sum, CachedRegion, Cell, Word,
+            select, CachedRegion, Cell, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
@@ -29,7 +29,8 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 #[derive(Clone, Debug)]
 pub(crate) struct SarGadget<F> {
     same_context: SameContextGadget<F>,
-    shift: Word<F>,
+    // Shift word, split into its first byte and whether it's less than 256.
+    shift: ShiftAmountGadget<F>,
     a: Word<F>,
     b: Word<F>,
     // Each of the four `a64s` limbs is split into two parts (`a64s_lo` and `a64s_hi`) at position
@@ -53,8 +54,6 @@ pub(crate) struct SarGadget<F> {
     shf_div64_lt_4: LtGadget<F, 1>,
     // Verify `shf_mod64 < 64`.
     shf_mod64_lt_64: LtGadget<F, 1>,
-    // Identify if `shift` is less than 256 or not.
-    shf_lt256: IsZeroGadget<F>,
     // shf_div64 == 0
     shf_lo_div64_eq0: IsZeroGadget<F>,
     // shf_div64 == 1
@@ -77,11 +76,11 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
 
-        let shift = cb.query_word_rlc();
+        let shift = ShiftAmountGadget::construct(cb);
         let a = cb.query_word_rlc();
         let b = cb.query_word_rlc();
 
-        cb.stack_pop(shift.expr());
+        cb.stack_pop(shift.shift().expr());
         cb.stack_pop(a.expr());
         cb.stack_push(b.expr());
 
@@ -93,7 +92,6 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
         let p_hi = cb.query_cell();
         let p_top = cb.query_cell();
         let is_neg = LtGadget::construct(cb, 127.expr(), a.cells[31].expr());
-        let shf_lt256 = IsZeroGadget::construct(cb, sum::expr(&shift.cells[1..32]));
 
         for idx in 0..4 {
             cb.require_equal(
@@ -122,10 +120,10 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
         let shf_lo_div64_eq1 = IsEqualGadget::construct(cb, shf_div64.expr(), 1.expr());
         let shf_lo_div64_eq2 = IsEqualGadget::construct(cb, shf_div64.expr(), 2.expr());
         let shf_lo_div64_eq3 = IsEqualGadget::construct(cb, shf_div64.expr(), 3.expr());
-        let shf_div64_eq0 = shf_lt256.expr() * shf_lo_div64_eq0.expr();
-        let shf_div64_eq1 = shf_lt256.expr() * shf_lo_div64_eq1.expr();
-        let shf_div64_eq2 = shf_lt256.expr() * shf_lo_div64_eq2.expr();
-        let shf_div64_eq3 = shf_lt256.expr() * shf_lo_div64_eq3.expr();
+        let shf_div64_eq0 = shift.is_lt_256() * shf_lo_div64_eq0.expr();
+        let shf_div64_eq1 = shift.is_lt_256() * shf_lo_div64_eq1.expr();
+        let shf_div64_eq2 = shift.is_lt_256() * shf_lo_div64_eq2.expr();
+        let shf_div64_eq3 = shift.is_lt_256() * shf_lo_div64_eq3.expr();
 
         cb.require_equal(
             "Constrain merged b64s[0] value",
@@ -178,7 +176,7 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
         cb.require_equal("shf_mod64 < 64", shf_mod64_lt_64.expr(), 1.expr());
         cb.require_equal(
             "shift[0] == shf_mod64 + shf_div64 * 64",
-            shift.cells[0].expr(),
+            shift.low_byte(),
             shf_mod64.expr() + shf_div64.expr() * 64.expr(),
         );
 
@@ -246,7 +244,6 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
             is_neg,
             shf_div64_lt_4,
             shf_mod64_lt_64,
-            shf_lt256,
             shf_lo_div64_eq0,
             shf_lo_div64_eq1,
             shf_lo_div64_eq2,
@@ -269,13 +266,11 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
         let indices = [step.rw_indices[0], step.rw_indices[1], step.rw_indices[2]];
         let [shift, a, b] = indices.map(|idx| block.rws[idx].stack_value());
 
-        self.shift
-            .assign(region, offset, Some(shift.to_le_bytes()))?;
+        let (shf0, is_gte_256) = self.shift.assign(region, offset, shift)?;
         self.a.assign(region, offset, Some(a.to_le_bytes()))?;
         self.b.assign(region, offset, Some(b.to_le_bytes()))?;
 
         let is_neg = 127 < a.to_le_bytes()[31];
-        let shf0 = u64::from(shift.to_le_bytes()[0]);
         let shf_div64 = shf0 / 64;
         let shf_mod64 = shf0 % 64;
         let p_lo = 1 << shf_mod64;
@@ -285,11 +280,6 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
         } else {
             0
         };
-        let shf_lt256 = shift
-            .to_le_bytes()
-            .iter()
-            .fold(0, |acc, val| acc + u64::from(*val))
-            - shf0;
         let a64s = a.0;
         let mut a64s_lo = [0; 4];
         let mut a64s_hi = [0; 4];
@@ -302,7 +292,7 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
         } else {
             [0; 4]
         };
-        if shf_lt256 == 0 && shf_div64 < 4 {
+        if !is_gte_256 && shf_div64 < 4 {
             let idx = shf_div64 as usize;
             b64s[3 - idx] = a64s_hi[3] + p_top;
             for k in 0..3 - idx {
@@ -339,7 +329,6 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
             .assign(region, offset, F::from(shf_div64), 4.into())?;
         self.shf_mod64_lt_64
             .assign(region, offset, F::from(shf_mod64), 64.into())?;
-        self.shf_lt256.assign(region, offset, F::from(shf_lt256))?;
         self.shf_lo_div64_eq0
             .assign(region, offset, F::from(shf_div64))?;
         self.shf_lo_div64_eq1
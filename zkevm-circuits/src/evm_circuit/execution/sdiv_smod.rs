@@ -245,7 +245,10 @@ fn is_neg(x: U256) -> bool {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_word},
+        test_util::CircuitTestBuilder,
+    };
     use eth_types::{bytecode, evm_types::OpcodeId, Word};
     use mock::TestContext;
 
@@ -338,4 +341,87 @@ mod test {
         let divisor = rand_word();
         test_ok(OpcodeId::SMOD, dividend, divisor);
     }
+
+    fn neg(x: u64) -> Word {
+        Word::zero().overflowing_sub(Word::from(x)).0
+    }
+
+    /// Runs `opcode` on `a` and `b` and checks the pushed result.
+    fn test_result(opcode: OpcodeId, a: Word, b: Word, expected: Word) {
+        let bytecode = bytecode! {
+            PUSH32(b)
+            PUSH32(a)
+            .write_op(opcode)
+            STOP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .block_modifier(Box::new(move |block| {
+            let step = block.txs[0]
+                .steps
+                .iter()
+                .find(|step| step.execution_state == ExecutionState::SDIV_SMOD)
+                .unwrap();
+            assert_eq!(block.rws[step.rw_indices[2]].stack_value(), expected);
+        }))
+        .run();
+    }
+
+    #[test]
+    fn sdiv_gadget_int_min_by_minus_one() {
+        let int_min = Word::one() << 255;
+        // The quotient 1 << 255 doesn't fit in a signed word and wraps to
+        // INT_MIN.
+        test_result(OpcodeId::SDIV, int_min, neg(1), int_min);
+        test_result(OpcodeId::SDIV, int_min, 1.into(), int_min);
+        test_result(OpcodeId::SDIV, int_min, int_min, 1.into());
+        test_result(OpcodeId::SDIV, int_min, 2.into(), neg(1) << 254);
+    }
+
+    #[test]
+    fn sdiv_gadget_signs() {
+        test_result(OpcodeId::SDIV, 7.into(), 3.into(), 2.into());
+        test_result(OpcodeId::SDIV, neg(7), 3.into(), neg(2));
+        test_result(OpcodeId::SDIV, 7.into(), neg(3), neg(2));
+        test_result(OpcodeId::SDIV, neg(7), neg(3), 2.into());
+        test_result(OpcodeId::SDIV, neg(2), neg(3), 0.into());
+    }
+
+    #[test]
+    fn sdiv_gadget_zero_divisor() {
+        test_result(OpcodeId::SDIV, 7.into(), 0.into(), 0.into());
+        test_result(OpcodeId::SDIV, neg(7), 0.into(), 0.into());
+        test_result(OpcodeId::SDIV, Word::one() << 255, 0.into(), 0.into());
+    }
+
+    #[test]
+    fn smod_gadget_sign_of_dividend() {
+        test_result(OpcodeId::SMOD, 7.into(), 3.into(), 1.into());
+        test_result(OpcodeId::SMOD, neg(7), 3.into(), neg(1));
+        test_result(OpcodeId::SMOD, 7.into(), neg(3), 1.into());
+        test_result(OpcodeId::SMOD, neg(7), neg(3), neg(1));
+        // No remainder keeps the result at 0 whatever the signs.
+        test_result(OpcodeId::SMOD, neg(6), 3.into(), 0.into());
+    }
+
+    #[test]
+    fn smod_gadget_int_min() {
+        let int_min = Word::one() << 255;
+        test_result(OpcodeId::SMOD, int_min, neg(1), 0.into());
+        test_result(OpcodeId::SMOD, int_min, int_min, 0.into());
+        // 2**255 % 3 == 2
+        test_result(OpcodeId::SMOD, int_min, 3.into(), neg(2));
+        test_result(OpcodeId::SMOD, int_min, neg(3), neg(2));
+        test_result(OpcodeId::SMOD, 5.into(), int_min, 5.into());
+        test_result(OpcodeId::SMOD, neg(5), int_min, neg(5));
+    }
+
+    #[test]
+    fn smod_gadget_zero_divisor() {
+        test_result(OpcodeId::SMOD, 7.into(), 0.into(), 0.into());
+        test_result(OpcodeId::SMOD, neg(7), 0.into(), 0.into());
+        test_result(OpcodeId::SMOD, Word::one() << 255, 0.into(), 0.into());
+    }
 }
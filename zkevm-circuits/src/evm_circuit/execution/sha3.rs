@@ -162,12 +162,12 @@ impl<F: Field> ExecutionGadget<F> for Sha3Gadget<F> {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{evm_circuit::step::ExecutionState, test_util::CircuitTestBuilder};
     use bus_mapping::{
         circuit_input_builder::CircuitsParams,
         evm::{gen_sha3_code, MemoryKind},
     };
-    use eth_types::{bytecode, Word};
+    use eth_types::{bytecode, evm_types::GasCost, word, Bytecode, Word};
     use mock::TestContext;
 
     fn test_ok(offset: usize, size: usize, mem_kind: MemoryKind) {
@@ -204,6 +204,72 @@ mod tests {
         test_ok(0x404, 0x505, MemoryKind::MoreThanSize);
     }
 
+    /// Runs `code` followed by `SHA3(offset, size)` and checks the digest the
+    /// SHA3 step pushes and its gas cost.
+    fn test_digest(mut code: Bytecode, offset: u64, size: u64, digest: Word, gas_cost: u64) {
+        code.append(&bytecode! {
+            PUSH32(size)
+            PUSH32(offset)
+            SHA3
+            STOP
+        });
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        )
+        .block_modifier(Box::new(move |block| {
+            let step = block.txs[0]
+                .steps
+                .iter()
+                .find(|step| step.execution_state == ExecutionState::SHA3)
+                .unwrap();
+            assert_eq!(block.rws[step.rw_indices[2]].stack_value(), digest);
+            assert_eq!(step.gas_cost, gas_cost);
+        }))
+        .run();
+    }
+
+    #[test]
+    fn sha3_gadget_empty_digest() {
+        test_digest(
+            Bytecode::default(),
+            0x00,
+            0x00,
+            word!("0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+            GasCost::SHA3.as_u64(),
+        );
+    }
+
+    #[test]
+    fn sha3_gadget_word_digest() {
+        // No memory expansion, the word is already stored.
+        test_digest(
+            bytecode! {
+                PUSH1(0x01)
+                PUSH1(0x00)
+                MSTORE
+            },
+            0x00,
+            0x20,
+            word!("0xb10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf6"),
+            GasCost::SHA3.as_u64() + GasCost::COPY_SHA3.as_u64(),
+        );
+    }
+
+    #[test]
+    fn sha3_gadget_expansion_digest() {
+        // Hashes the zeros of the 3rd word, expanding memory from 0 to 3 words.
+        test_digest(
+            Bytecode::default(),
+            0x40,
+            0x20,
+            word!("0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563"),
+            GasCost::SHA3.as_u64()
+                + GasCost::COPY_SHA3.as_u64()
+                + 3 * GasCost::MEMORY_EXPANSION_LINEAR_COEFF.as_u64(),
+        );
+    }
+
     #[test]
     fn sha3_gadget_overflow_offset_and_zero_size() {
         let bytecode = bytecode! {
@@ -5,14 +5,14 @@ use crate::{
         table::{FixedTableTag, Lookup},
         util::{
             self,
-            common_gadget::SameContextGadget,
+            common_gadget::{SameContextGadget, ShiftAmountGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
                 Transition::Delta,
             },
             from_bytes,
             math_gadget::{IsZeroGadget, LtWordGadget, MulAddWordsGadget},
-            sum, CachedRegion, Cell,
+            sum, CachedRegion,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
@@ -20,7 +20,7 @@ use crate::{
 };
 use bus_mapping::evm::OpcodeId;
 use eth_types::{Field, ToLittleEndian, U256};
-use halo2_proofs::{circuit::Value, plonk::Error};
+use halo2_proofs::plonk::Error;
 
 /// ShlShrGadget verifies opcode SHL and SHR.
 /// For SHL, verify pop1 * (2^pop2) % 2^256 == push;
@@ -33,14 +33,10 @@ pub(crate) struct ShlShrGadget<F> {
     divisor: util::Word<F>,
     remainder: util::Word<F>,
     dividend: util::Word<F>,
-    /// Shift word
-    shift: util::Word<F>,
-    /// First byte of shift word
-    shf0: Cell<F>,
+    /// Shift word, split into its first byte and whether it's less than 256
+    shift: ShiftAmountGadget<F>,
     /// Gadget that verifies quotient * divisor + remainder = dividend
     mul_add_words: MulAddWordsGadget<F>,
-    /// Identify if `shift` is less than 256 or not
-    shf_lt256: IsZeroGadget<F>,
     /// Check if divisor is zero
     divisor_is_zero: IsZeroGadget<F>,
     /// Check if remainder is zero
@@ -63,12 +59,10 @@ impl<F: Field> ExecutionGadget<F> for ShlShrGadget<F> {
         let divisor = cb.query_word_rlc();
         let remainder = cb.query_word_rlc();
         let dividend = cb.query_word_rlc();
-        let shift = cb.query_word_rlc();
-        let shf0 = cb.query_cell();
+        let shift = ShiftAmountGadget::construct(cb);
 
         let mul_add_words =
             MulAddWordsGadget::construct(cb, [&quotient, &divisor, &remainder, &dividend]);
-        let shf_lt256 = IsZeroGadget::construct(cb, sum::expr(&shift.cells[1..32]));
         let divisor_is_zero = IsZeroGadget::construct(cb, sum::expr(&divisor.cells));
         let remainder_is_zero = IsZeroGadget::construct(cb, sum::expr(&remainder.cells));
         let remainder_lt_divisor = LtWordGadget::construct(cb, &remainder, &divisor);
@@ -76,26 +70,16 @@ impl<F: Field> ExecutionGadget<F> for ShlShrGadget<F> {
         // Constrain stack pops and pushes as:
         // - for SHL, two pops are shift and quotient, and push is dividend.
         // - for SHR, two pops are shift and dividend, and push is quotient.
-        cb.stack_pop(shift.expr());
+        cb.stack_pop(shift.shift().expr());
         cb.stack_pop(is_shl.expr() * quotient.expr() + is_shr.expr() * dividend.expr());
         cb.stack_push(
             (is_shl.expr() * dividend.expr() + is_shr.expr() * quotient.expr())
                 * (1.expr() - divisor_is_zero.expr()),
         );
 
-        cb.require_zero(
-            "shf0 == shift.cells[0]",
-            shf0.expr() - shift.cells[0].expr(),
-        );
-
-        cb.require_zero(
-            "shift == shift.cells[0] when divisor != 0",
-            (1.expr() - divisor_is_zero.expr()) * (shift.expr() - shift.cells[0].expr()),
-        );
-
         cb.require_zero(
             "shift < 256 when divisor != 0 or shift >= 256 when divisor == 0",
-            1.expr() - divisor_is_zero.expr() - shf_lt256.expr(),
+            divisor_is_zero.expr() - shift.is_gte_256(),
         );
 
         cb.require_zero(
@@ -114,7 +98,8 @@ impl<F: Field> ExecutionGadget<F> for ShlShrGadget<F> {
         );
 
         // Constrain divisor_lo == 2^shf0 when shf0 < 128, and
-        // divisor_hi == 2^(128 - shf0) otherwise.
+        // divisor_hi == 2^(128 - shf0) otherwise, with shf0 the first byte of
+        // shift.
         let divisor_lo = from_bytes::expr(&divisor.cells[..16]);
         let divisor_hi = from_bytes::expr(&divisor.cells[16..]);
         cb.condition(1.expr() - divisor_is_zero.expr(), |cb| {
@@ -122,7 +107,7 @@ impl<F: Field> ExecutionGadget<F> for ShlShrGadget<F> {
                 "Pow2 lookup of shf0, divisor_lo and divisor_hi",
                 Lookup::Fixed {
                     tag: FixedTableTag::Pow2.expr(),
-                    values: [shf0.expr(), divisor_lo.expr(), divisor_hi.expr()],
+                    values: [shift.low_byte(), divisor_lo.expr(), divisor_hi.expr()],
                 },
             );
         });
@@ -144,9 +129,7 @@ impl<F: Field> ExecutionGadget<F> for ShlShrGadget<F> {
             remainder,
             dividend,
             shift,
-            shf0,
             mul_add_words,
-            shf_lt256,
             divisor_is_zero,
             remainder_is_zero,
             remainder_lt_divisor,
@@ -165,17 +148,11 @@ impl<F: Field> ExecutionGadget<F> for ShlShrGadget<F> {
         self.same_context.assign_exec_step(region, offset, step)?;
         let indices = [step.rw_indices[0], step.rw_indices[1], step.rw_indices[2]];
         let [pop1, pop2, push] = indices.map(|idx| block.rws[idx].stack_value());
-        let shf0 = u64::from(pop1.to_le_bytes()[0]);
-        let shf_lt256 = pop1
-            .to_le_bytes()
-            .iter()
-            .try_fold(0_u64, |acc, val| acc.checked_add(u64::from(*val)))
-            .unwrap()
-            - shf0;
-        let divisor = if shf_lt256 == 0 {
-            U256::from(1) << shf0
-        } else {
+        let (shf0, is_gte_256) = self.shift.assign(region, offset, pop1)?;
+        let divisor = if is_gte_256 {
             U256::from(0)
+        } else {
+            U256::from(1) << shf0
         };
 
         let (quotient, remainder, dividend) = match step.opcode.unwrap() {
@@ -191,13 +168,8 @@ impl<F: Field> ExecutionGadget<F> for ShlShrGadget<F> {
             .assign(region, offset, Some(remainder.to_le_bytes()))?;
         self.dividend
             .assign(region, offset, Some(dividend.to_le_bytes()))?;
-        self.shift
-            .assign(region, offset, Some(pop1.to_le_bytes()))?;
-        self.shf0
-            .assign(region, offset, Value::known(F::from(shf0)))?;
         self.mul_add_words
             .assign(region, offset, [quotient, divisor, remainder, dividend])?;
-        self.shf_lt256.assign(region, offset, F::from(shf_lt256))?;
         let divisor_sum = (0..32).fold(0, |acc, idx| acc + divisor.byte(idx) as u64);
         self.divisor_is_zero
             .assign(region, offset, F::from(divisor_sum))?;
@@ -20,7 +20,10 @@ use crate::{
     util::Expr,
 };
 
-use eth_types::{evm_types::GasCost, Field, ToScalar};
+use eth_types::{
+    evm_types::{GasCost, HardFork},
+    Field, ToScalar,
+};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -244,6 +247,7 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
 
 #[derive(Clone, Debug)]
 pub(crate) struct SstoreTxRefundGadget<F> {
+    hard_fork: HardFork,
     tx_refund_old: Cell<F>,
     tx_refund_new: Expression<F>,
     value: Cell<F>,
@@ -301,13 +305,16 @@ impl<F: Field> SstoreTxRefundGadget<F> {
         let recreate_slot =
             not::expr(prev_eq_value) * not::expr(original_eq_prev) * (value_prev_is_zero);
 
+        let hard_fork = cb.hard_fork();
+        let clears_schedule = hard_fork.sstore_clears_schedule();
         let tx_refund_new = tx_refund_old.expr()
-            + delete_slot * GasCost::SSTORE_CLEARS_SCHEDULE.expr()
+            + delete_slot * clears_schedule.expr()
             + reset_existing * (GasCost::SSTORE_RESET.expr() - GasCost::WARM_ACCESS.expr())
             + reset_inexistent * (GasCost::SSTORE_SET.expr() - GasCost::WARM_ACCESS.expr())
-            - recreate_slot * (GasCost::SSTORE_CLEARS_SCHEDULE.expr());
+            - recreate_slot * clears_schedule.expr();
 
         Self {
+            hard_fork,
             value,
             value_prev,
             original_value,
@@ -373,7 +380,13 @@ impl<F: Field> SstoreTxRefundGadget<F> {
             region.word_rlc(value_prev),
         )?;
         debug_assert_eq!(
-            calc_expected_tx_refund(tx_refund_old, value, value_prev, original_value),
+            calc_expected_tx_refund(
+                self.hard_fork,
+                tx_refund_old,
+                value,
+                value_prev,
+                original_value,
+            ),
             tx_refund
         );
         Ok(())
@@ -381,6 +394,7 @@ impl<F: Field> SstoreTxRefundGadget<F> {
 }
 
 fn calc_expected_tx_refund(
+    hard_fork: HardFork,
     tx_refund_old: u64,
     value: eth_types::Word,
     value_prev: eth_types::Word,
@@ -391,6 +405,7 @@ fn calc_expected_tx_refund(
     // understanding and comparison.
 
     let mut tx_refund_new = tx_refund_old;
+    let clears_schedule = hard_fork.sstore_clears_schedule().as_u64();
 
     // The "clearing slot refund" and "resetting value refund" are ADDED together,
     // they are NOT MUTUALLY EXCLUSIVE.
@@ -411,11 +426,11 @@ fn calc_expected_tx_refund(
         if !original_value.is_zero() {
             if value_prev.is_zero() {
                 // recreate slot (2.2.1.1)
-                tx_refund_new -= GasCost::SSTORE_CLEARS_SCHEDULE.as_u64()
+                tx_refund_new -= clears_schedule
             }
             if value.is_zero() {
                 // delete slot (2.2.1.2)
-                tx_refund_new += GasCost::SSTORE_CLEARS_SCHEDULE.as_u64()
+                tx_refund_new += clears_schedule
             }
         }
 
@@ -437,8 +452,10 @@ fn calc_expected_tx_refund(
 #[cfg(test)]
 mod test {
 
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, Word};
+    use super::calc_expected_tx_refund;
+    use crate::{evm_circuit::EvmCircuit, test_util::CircuitTestBuilder};
+    use eth_types::{bytecode, evm_types::HardFork, Word};
+    use halo2_proofs::{halo2curves::bn256::Fr, plonk::ConstraintSystem};
     use mock::{test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
 
     #[test]
@@ -502,6 +519,53 @@ mod test {
         );
     }
 
+    #[test]
+    fn sstore_refund_clear_slot_by_hard_fork() {
+        // original_value == value_prev != 0, value == 0
+        let (value, value_prev, original_value) = (Word::zero(), 0x060504.into(), 0x060504.into());
+        assert_eq!(
+            calc_expected_tx_refund(HardFork::Berlin, 0, value, value_prev, original_value),
+            15000
+        );
+        assert_eq!(
+            calc_expected_tx_refund(HardFork::London, 0, value, value_prev, original_value),
+            4800
+        );
+    }
+
+    /// Renders the constraints of the gates named `gate_name` with the EVM
+    /// circuit configured for `hard_fork`.
+    fn gate_constraints(hard_fork: HardFork, gate_name: &str) -> String {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        EvmCircuit::<Fr>::configure_with_hard_fork(&mut meta, hard_fork);
+        meta.gates()
+            .iter()
+            .filter(|gate| gate.name() == gate_name)
+            .map(|gate| format!("{:?}", gate.polynomials()))
+            .collect()
+    }
+
+    #[test]
+    fn sstore_gate_follows_hard_fork() {
+        // The refund rules of the fork reach the SSTORE and EndTx constraints,
+        // while gadgets without refunds are configured the same.
+        for gate_name in ["SSTORE", "EndTx"] {
+            assert_ne!(
+                gate_constraints(HardFork::Berlin, gate_name),
+                gate_constraints(HardFork::London, gate_name),
+                "{gate_name}"
+            );
+        }
+        assert_eq!(
+            gate_constraints(HardFork::Berlin, "ADD_SUB"),
+            gate_constraints(HardFork::London, "ADD_SUB")
+        );
+        assert_eq!(
+            gate_constraints(HardFork::default(), "SSTORE"),
+            gate_constraints(HardFork::London, "SSTORE")
+        );
+    }
+
     fn test_ok(key: Word, value: Word, value_prev: Word, original_value: Word) {
         // Here we use two bytecodes to test both is_persistent(STOP) or not(REVERT)
         // Besides, in bytecode we use two SSTOREs,
@@ -135,7 +135,7 @@ impl<F: Field> ExecutionGadget<F> for StopGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{evm_circuit::step::ExecutionState, test_util::CircuitTestBuilder};
     use eth_types::{address, bytecode, Bytecode, Word};
 
     use itertools::Itertools;
@@ -221,4 +221,83 @@ mod test {
             test_ok(bytecode, is_root);
         }
     }
+
+    #[test]
+    fn stop_gadget_internal_call_empty_return_data() {
+        // The caller first calls a contract which returns 32 bytes, then a
+        // contract which halts with STOP. After the latter the caller must
+        // resume with empty return data.
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000123"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(bytecode! {
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0x20)
+                        GAS
+                        CALL
+                        POP
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0x30)
+                        GAS
+                        CALL
+                        POP
+                        RETURNDATASIZE
+                        STOP
+                    });
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000000020"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(bytecode! {
+                        PUSH1(0x20)
+                        PUSH1(0)
+                        RETURN
+                    });
+                accs[3]
+                    .address(address!("0x0000000000000000000000000000000000000030"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(bytecode! {
+                        PUSH1(0)
+                        STOP
+                    });
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(100000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                let steps = &block.txs[0].steps;
+                let stops = steps
+                    .iter()
+                    .filter(|step| step.execution_state == ExecutionState::STOP)
+                    .count();
+                assert_eq!(stops, 2, "expect internal and root STOP");
+                let step = steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::RETURNDATASIZE)
+                    .unwrap();
+                assert_eq!(block.rws[step.rw_indices[1]].stack_value(), Word::zero());
+            }))
+            .run();
+    }
 }
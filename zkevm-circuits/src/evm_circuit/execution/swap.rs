@@ -83,8 +83,12 @@ impl<F: Field> ExecutionGadget<F> for SwapGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, evm_types::OpcodeId, Word};
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_word, witness::Rw},
+        test_util::{assert_gadget_rejects, CircuitTestBuilder},
+    };
+    use eth_types::{bytecode, evm_types::OpcodeId, Bytecode, Word};
+    use itertools::Itertools;
     use mock::TestContext;
 
     fn test_ok(opcode: OpcodeId, lhs: Word, rhs: Word) {
@@ -116,6 +120,80 @@ mod test {
         test_ok(OpcodeId::SWAP16, Word::from(0x030201), Word::from(0x040506));
     }
 
+    /// Pushes `1..=n + 1`, swaps with `opcode` and pops the whole stack, so
+    /// that every slot is read back after the swap.
+    fn swap_then_pop(opcode: OpcodeId) -> Bytecode {
+        let n = opcode.postfix().expect("opcode with postfix") as usize;
+
+        let mut bytecode = Bytecode::default();
+        for value in 1..=n + 1 {
+            bytecode.push(1, Word::from(value));
+        }
+        bytecode.write_op(opcode);
+        for _ in 0..=n {
+            bytecode.op_pop();
+        }
+        bytecode.op_stop();
+        bytecode
+    }
+
+    fn test_round_trip(opcode: OpcodeId) {
+        let n = opcode.postfix().expect("opcode with postfix") as usize;
+        // Stack from the top before the swap, with the top and the n-th slot
+        // exchanged.
+        let mut expected = (1..=n + 1).rev().map(Word::from).collect_vec();
+        expected.swap(0, n);
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(swap_then_pop(opcode)).unwrap(),
+        )
+        .block_modifier(Box::new(move |block| {
+            let steps = &block.txs[0].steps;
+            let swap_step = steps
+                .iter()
+                .find(|step| step.execution_state == ExecutionState::SWAP)
+                .unwrap();
+            let [read_n, read_top, write_n, write_top] =
+                [0, 1, 2, 3].map(|i| &block.rws[swap_step.rw_indices[i]]);
+            assert!(!read_n.is_write() && !read_top.is_write());
+            assert!(write_n.is_write() && write_top.is_write());
+            assert_eq!(write_n.stack_value(), read_top.stack_value());
+            assert_eq!(write_top.stack_value(), read_n.stack_value());
+
+            let popped = steps
+                .iter()
+                .filter(|step| step.execution_state == ExecutionState::POP)
+                .map(|step| block.rws[step.rw_indices[0]].stack_value())
+                .collect_vec();
+            assert_eq!(popped, expected);
+        }))
+        .run();
+
+        // Writing back the value read from the top, instead of the one read
+        // from the n-th slot, must be rejected.
+        assert_gadget_rejects(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(swap_then_pop(opcode)).unwrap(),
+            Box::new(|block| {
+                let swap_step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state == ExecutionState::SWAP)
+                    .unwrap();
+                let top = block.rws[swap_step.rw_indices[1]].stack_value();
+                let (tag, idx) = swap_step.rw_indices[3];
+                if let Rw::Stack { value, .. } = &mut block.rws.0.get_mut(&tag).unwrap()[idx] {
+                    *value = top;
+                }
+            }),
+        );
+    }
+
+    #[test]
+    fn swap_gadget_round_trip() {
+        test_round_trip(OpcodeId::SWAP1);
+        test_round_trip(OpcodeId::SWAP16);
+    }
+
     #[test]
     #[ignore]
     fn swap_gadget_rand() {
@@ -5,6 +5,7 @@ use halo2_proofs::{
     plonk::{Circuit, ConstraintSystem},
 };
 use std::{collections::HashMap, sync::LazyLock};
+use strum::IntoEnumIterator;
 
 // Step dimension
 pub(crate) const STEP_WIDTH: usize = 144;
@@ -144,6 +145,15 @@ pub(crate) const N_BYTES_CALLDATASIZE: usize = N_BYTES_U64;
 pub(crate) static EXECUTION_STATE_HEIGHT_MAP: LazyLock<HashMap<ExecutionState, usize>> =
     LazyLock::new(get_step_height_map);
 
+// Execution states with a gadget configured in the evm circuit, in
+// `ExecutionState` order
+pub(crate) static IMPLEMENTED_EXECUTION_STATES: LazyLock<Vec<ExecutionState>> =
+    LazyLock::new(|| {
+        ExecutionState::iter()
+            .filter(|state| EXECUTION_STATE_HEIGHT_MAP.contains_key(state))
+            .collect()
+    });
+
 fn get_step_height_map() -> HashMap<ExecutionState, usize> {
     let mut meta = ConstraintSystem::<Fr>::default();
     let circuit = EvmCircuit::configure(&mut meta);
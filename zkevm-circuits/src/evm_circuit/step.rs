@@ -1,7 +1,10 @@
 use super::util::{CachedRegion, CellManager, CellType};
 use crate::{
     evm_circuit::{
-        param::{EXECUTION_STATE_HEIGHT_MAP, MAX_STEP_HEIGHT, STEP_STATE_HEIGHT, STEP_WIDTH},
+        param::{
+            EXECUTION_STATE_HEIGHT_MAP, IMPLEMENTED_EXECUTION_STATES, MAX_STEP_HEIGHT,
+            STEP_STATE_HEIGHT, STEP_WIDTH,
+        },
         util::Cell,
         witness::{Block, Call, ExecStep},
     },
@@ -390,6 +393,100 @@ impl ExecutionState {
         .collect()
     }
 
+    /// Returns the execution state of a step executing `op` without error, or
+    /// `None` if the opcode is not supported.
+    /// A step ending in an error has a state that depends on the runtime
+    /// context (stack, gas left, call depth...), see `From<&ExecError>`.
+    pub(crate) fn from_opcode(op: OpcodeId) -> Option<Self> {
+        if op.is_dup() {
+            return Some(Self::DUP);
+        }
+        if op.is_push() {
+            return Some(Self::PUSH);
+        }
+        if op.is_swap() {
+            return Some(Self::SWAP);
+        }
+        if op.is_log() {
+            return Some(Self::LOG);
+        }
+
+        let execution_state = match op {
+            OpcodeId::ADD | OpcodeId::SUB => Self::ADD_SUB,
+            OpcodeId::ADDMOD => Self::ADDMOD,
+            OpcodeId::ADDRESS => Self::ADDRESS,
+            OpcodeId::BALANCE => Self::BALANCE,
+            OpcodeId::MUL | OpcodeId::DIV | OpcodeId::MOD => Self::MUL_DIV_MOD,
+            OpcodeId::MULMOD => Self::MULMOD,
+            OpcodeId::SDIV | OpcodeId::SMOD => Self::SDIV_SMOD,
+            OpcodeId::EQ | OpcodeId::LT | OpcodeId::GT => Self::CMP,
+            OpcodeId::SLT | OpcodeId::SGT => Self::SCMP,
+            OpcodeId::SIGNEXTEND => Self::SIGNEXTEND,
+            OpcodeId::STOP => Self::STOP,
+            OpcodeId::AND => Self::BITWISE,
+            OpcodeId::XOR => Self::BITWISE,
+            OpcodeId::OR => Self::BITWISE,
+            OpcodeId::NOT => Self::NOT,
+            OpcodeId::EXP => Self::EXP,
+            OpcodeId::POP => Self::POP,
+            OpcodeId::BYTE => Self::BYTE,
+            OpcodeId::MLOAD => Self::MEMORY,
+            OpcodeId::MSTORE => Self::MEMORY,
+            OpcodeId::MSTORE8 => Self::MEMORY,
+            OpcodeId::JUMPDEST => Self::JUMPDEST,
+            OpcodeId::JUMP => Self::JUMP,
+            OpcodeId::JUMPI => Self::JUMPI,
+            OpcodeId::GASPRICE => Self::GASPRICE,
+            OpcodeId::PC => Self::PC,
+            OpcodeId::MSIZE => Self::MSIZE,
+            OpcodeId::CALLER => Self::CALLER,
+            OpcodeId::CALLVALUE => Self::CALLVALUE,
+            OpcodeId::EXTCODEHASH => Self::EXTCODEHASH,
+            OpcodeId::EXTCODESIZE => Self::EXTCODESIZE,
+            OpcodeId::BLOCKHASH => Self::BLOCKHASH,
+            OpcodeId::TIMESTAMP | OpcodeId::NUMBER | OpcodeId::GASLIMIT => Self::BLOCKCTXU64,
+            OpcodeId::COINBASE => Self::BLOCKCTXU160,
+            OpcodeId::BASEFEE => Self::BLOCKCTXU256,
+            #[cfg(not(feature = "scroll"))]
+            OpcodeId::DIFFICULTY => Self::BLOCKCTXU256,
+            #[cfg(feature = "scroll")]
+            OpcodeId::DIFFICULTY => Self::DIFFICULTY,
+            OpcodeId::GAS => Self::GAS,
+            OpcodeId::SAR => Self::SAR,
+            OpcodeId::SELFBALANCE => Self::SELFBALANCE,
+            OpcodeId::SHA3 => Self::SHA3,
+            OpcodeId::SHL | OpcodeId::SHR => Self::SHL_SHR,
+            OpcodeId::SLOAD => Self::SLOAD,
+            OpcodeId::SSTORE => Self::SSTORE,
+            OpcodeId::CALLDATASIZE => Self::CALLDATASIZE,
+            OpcodeId::CALLDATACOPY => Self::CALLDATACOPY,
+            OpcodeId::CHAINID => Self::CHAINID,
+            OpcodeId::ISZERO => Self::ISZERO,
+            OpcodeId::CALL | OpcodeId::CALLCODE | OpcodeId::DELEGATECALL | OpcodeId::STATICCALL => {
+                Self::CALL_OP
+            }
+            OpcodeId::ORIGIN => Self::ORIGIN,
+            OpcodeId::CODECOPY => Self::CODECOPY,
+            OpcodeId::CALLDATALOAD => Self::CALLDATALOAD,
+            OpcodeId::CODESIZE => Self::CODESIZE,
+            OpcodeId::EXTCODECOPY => Self::EXTCODECOPY,
+            OpcodeId::RETURN | OpcodeId::REVERT => Self::RETURN_REVERT,
+            OpcodeId::RETURNDATASIZE => Self::RETURNDATASIZE,
+            OpcodeId::RETURNDATACOPY => Self::RETURNDATACOPY,
+            OpcodeId::CREATE => Self::CREATE,
+            OpcodeId::CREATE2 => Self::CREATE2,
+            OpcodeId::SELFDESTRUCT => Self::SELFDESTRUCT,
+            _ => return None,
+        };
+        Some(execution_state)
+    }
+
+    /// Returns every execution state which has a gadget registered in the
+    /// evm circuit. Steps in any other state can't be proven.
+    pub fn implemented() -> &'static [ExecutionState] {
+        &IMPLEMENTED_EXECUTION_STATES
+    }
+
     pub fn get_step_height_option(&self) -> Option<usize> {
         EXECUTION_STATE_HEIGHT_MAP.get(self).copied()
     }
@@ -695,3 +792,41 @@ impl<F: Field> Step<F> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execution_state_from_opcode() {
+        assert_eq!(
+            ExecutionState::from_opcode(OpcodeId::CHAINID),
+            Some(ExecutionState::CHAINID)
+        );
+        assert_eq!(
+            ExecutionState::from_opcode(OpcodeId::JUMP),
+            Some(ExecutionState::JUMP)
+        );
+        assert_eq!(
+            ExecutionState::from_opcode(OpcodeId::PUSH32),
+            Some(ExecutionState::PUSH)
+        );
+        assert_eq!(ExecutionState::from_opcode(OpcodeId::INVALID(0xfe)), None);
+    }
+
+    // The opcodes looked up by the circuit for a non error state must be mapped
+    // back to that state when building the witness.
+    #[test]
+    fn execution_state_from_opcode_matches_responsible_opcodes() {
+        for state in ExecutionState::iter().filter(|state| !state.halts_in_exception()) {
+            for responsible_op in state.responsible_opcodes() {
+                assert_eq!(
+                    ExecutionState::from_opcode(responsible_op.opcode()),
+                    Some(state),
+                    "{:?} is responsible for {state:?}",
+                    responsible_op.opcode()
+                );
+            }
+        }
+    }
+}
@@ -10,7 +10,7 @@ use halo2_proofs::plonk::Expression;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-#[derive(Clone, Copy, Debug, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
 pub enum FixedTableTag {
     Zero = 0,
     Range3,
@@ -199,6 +199,19 @@ impl<F: Field> RwValues<F> {
     }
 }
 
+/// The table and number of input columns of a lookup a gadget adds, which
+/// lets an audit list the lookups of each gadget.
+#[cfg(any(feature = "test", test))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct LookupDescriptor {
+    /// Name the lookup was added with.
+    pub(crate) name: String,
+    /// Table the lookup queries.
+    pub(crate) table: Table,
+    /// Number of table columns the lookup inputs are matched against.
+    pub(crate) num_columns: usize,
+}
+
 #[derive(Clone, Debug)]
 pub(crate) enum Lookup<F> {
     /// Lookup to fixed table, which contains several pre-built tables such as
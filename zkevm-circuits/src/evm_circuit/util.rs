@@ -11,7 +11,7 @@ use crate::{
     witness::{Block, ExecStep, Rw, RwMap},
 };
 use bus_mapping::state_db::CodeDB;
-use eth_types::{Address, Field, ToLittleEndian, ToWord, U256};
+use eth_types::{evm_types::GasCost, Address, Field, ToLittleEndian, ToWord, U256};
 use halo2_proofs::{
     circuit::{AssignedCell, Region, Value},
     halo2curves::group::ff::BatchInvert,
@@ -560,6 +560,12 @@ impl<F: Field, const N: usize> Expr<F> for RandomLinearCombination<F, N> {
     }
 }
 
+/// A 256-bit EVM word as the RLC of its 32 little-endian bytes, which is how
+/// the rw, block, tx and copy tables expect values that may not fit in a field
+/// element. Values known to fit, like the chain id or an address, are looked up
+/// as `from_bytes::expr` of the cells of a shorter `RandomLinearCombination`
+/// instead. Gadgets working on `(lo, hi)` 128-bit limbs are connected to a
+/// `Word` by `WordReprConsistencyGadget`.
 pub(crate) type Word<F> = RandomLinearCombination<F, 32>;
 pub(crate) type U64Word<F> = RandomLinearCombination<F, N_BYTES_U64>;
 pub(crate) type MemoryAddress<F> = RandomLinearCombination<F, N_BYTES_MEMORY_ADDRESS>;
@@ -717,6 +723,11 @@ pub(crate) fn transpose_val_ret<F, E>(value: Value<Result<F, E>>) -> Result<Valu
     ret
 }
 
+/// Returns the gas charged for depositing `code_len` bytes of deployed code
+pub(crate) fn code_deposit_cost(code_len: usize) -> u64 {
+    GasCost::CODE_DEPOSIT_BYTE_COST.as_u64() * code_len as u64
+}
+
 pub(crate) fn is_precompiled(address: &Address) -> bool {
     address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
 }
@@ -7,7 +7,10 @@ use super::{
 };
 use crate::{
     evm_circuit::{
-        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64},
+        param::{
+            N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE,
+            N_BYTES_PROGRAM_COUNTER, N_BYTES_U64,
+        },
         step::ExecutionState,
         table::{FixedTableTag, Lookup},
         util::{
@@ -24,8 +27,11 @@ use crate::{
     witness::{Block, Call, ExecStep},
 };
 use either::Either;
-use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar, U256};
-use gadgets::util::{select, sum};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToLittleEndian, ToScalar, U256,
+};
+use gadgets::util::{and, select, sum};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -100,8 +106,13 @@ impl<F: Field> SameContextGadget<F> {
         self.opcode
             .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
 
-        self.sufficient_gas_left
-            .assign(region, offset, F::from(step.gas_left - step.gas_cost))?;
+        // Computed in the field, so that a cost above gas_left is assigned as a
+        // negative next gas_left and rejected by the range check.
+        self.sufficient_gas_left.assign(
+            region,
+            offset,
+            F::from(step.gas_left) - F::from(step.gas_cost),
+        )?;
 
         Ok(())
     }
@@ -1288,6 +1299,74 @@ impl<F: Field, MemAddrGadget: CommonMemoryAddressGadget<F>, const IS_SUCCESS_CAL
     }
 }
 
+/// Marks an account, or an account storage slot if `storage_key` is given, as
+/// warm in the tx access list, and returns whether it was already warm
+/// together with the access gas cost.
+#[derive(Clone, Debug)]
+pub(crate) struct AccessListGadget<F> {
+    is_warm: Cell<F>,
+    gas_cost: Expression<F>,
+}
+
+impl<F: Field> AccessListGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        tx_id: Expression<F>,
+        address: Expression<F>,
+        storage_key: Option<Expression<F>>,
+        reversion_info: &mut ReversionInfo<F>,
+    ) -> Self {
+        let is_warm = cb.query_bool();
+        let cold_gas_cost = if let Some(storage_key) = storage_key {
+            cb.account_storage_access_list_write(
+                tx_id,
+                address,
+                storage_key,
+                1.expr(),
+                is_warm.expr(),
+                Some(reversion_info),
+            );
+            GasCost::COLD_SLOAD
+        } else {
+            cb.account_access_list_write(
+                tx_id,
+                address,
+                1.expr(),
+                is_warm.expr(),
+                Some(reversion_info),
+            );
+            GasCost::COLD_ACCOUNT_ACCESS
+        };
+        let gas_cost = select::expr(
+            is_warm.expr(),
+            GasCost::WARM_ACCESS.expr(),
+            cold_gas_cost.expr(),
+        );
+
+        Self { is_warm, gas_cost }
+    }
+
+    pub(crate) fn is_warm(&self) -> Expression<F> {
+        self.is_warm.expr()
+    }
+
+    pub(crate) fn gas_cost(&self) -> Expression<F> {
+        self.gas_cost.clone()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        is_warm: bool,
+    ) -> Result<(), Error> {
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm)))?;
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct SloadGasGadget<F> {
     is_warm: Expression<F>,
@@ -1713,6 +1792,159 @@ impl<F: Field, const VALID_BYTES: usize> WordByteRangeGadget<F, VALID_BYTES> {
     }
 }
 
+/// Split the shift amount of SHL, SHR and SAR, popped from the stack as a
+/// word, into its low byte and whether it's `>= 256`, i.e. whether any higher
+/// byte is non-zero. The result of a shift by `>= 256` no longer depends on
+/// the low byte, so the gadgets branch on `is_gte_256` first.
+#[derive(Clone, Debug)]
+pub(crate) struct ShiftAmountGadget<F> {
+    shift: WordByteRangeGadget<F, 1>,
+}
+
+impl<F: Field> ShiftAmountGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        Self {
+            shift: WordByteRangeGadget::construct(cb),
+        }
+    }
+
+    /// Return the low byte of the shift and whether the shift is `>= 256`.
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        shift: U256,
+    ) -> Result<(u64, bool), Error> {
+        let is_lt_256 = self.shift.assign(region, offset, shift)?;
+
+        Ok((u64::from(shift.to_le_bytes()[0]), !is_lt_256))
+    }
+
+    pub(crate) fn shift(&self) -> &Word<F> {
+        self.shift.original_ref()
+    }
+
+    pub(crate) fn low_byte(&self) -> Expression<F> {
+        self.shift.valid_value()
+    }
+
+    pub(crate) fn is_gte_256(&self) -> Expression<F> {
+        self.shift.overflow()
+    }
+
+    pub(crate) fn is_lt_256(&self) -> Expression<F> {
+        self.shift.not_overflow()
+    }
+}
+
+/// Check if the jump destination, popped from the stack as a word, is valid in
+/// the current bytecode: it's less than the code length and points at a
+/// JUMPDEST opcode instead of push data. JUMP and JUMPI require a valid
+/// destination, while ErrorInvalidJump requires an invalid one.
+#[derive(Clone, Debug)]
+pub(crate) struct JumpDestValidGadget<F> {
+    code_len: Cell<F>,
+    dest: WordByteCapGadget<F, N_BYTES_PROGRAM_COUNTER>,
+    value: Cell<F>,
+    is_code: Cell<F>,
+    push_rlc: Cell<F>,
+    is_jump_dest: IsEqualGadget<F>,
+}
+
+impl<F: Field> JumpDestValidGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let code_len = cb.query_cell();
+        let dest = WordByteCapGadget::construct(cb, code_len.expr());
+        let value = cb.query_cell();
+        let is_code = cb.query_cell();
+        let push_rlc = cb.query_cell_phase2();
+        let is_jump_dest = IsEqualGadget::construct(cb, value.expr(), OpcodeId::JUMPDEST.expr());
+
+        // Look up bytecode length
+        cb.bytecode_length(cb.curr.state.code_hash.expr(), code_len.expr());
+
+        // If destination is in valid range, lookup for the value.
+        cb.condition(dest.lt_cap(), |cb| {
+            cb.bytecode_lookup(
+                cb.curr.state.code_hash.expr(),
+                dest.valid_value(),
+                is_code.expr(),
+                value.expr(),
+                push_rlc.expr(),
+            );
+        });
+
+        Self {
+            code_len,
+            dest,
+            value,
+            is_code,
+            push_rlc,
+            is_jump_dest,
+        }
+    }
+
+    /// Return true if the destination is valid.
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        call: &Call,
+        dest: U256,
+    ) -> Result<bool, Error> {
+        let code = block
+            .bytecodes
+            .get(&call.code_hash)
+            .expect("could not find current environment's bytecode");
+        let code_len = code.bytes.len() as u64;
+        self.code_len
+            .assign(region, offset, Value::known(F::from(code_len)))?;
+        self.dest.assign(region, offset, dest, F::from(code_len))?;
+
+        // set default value in case can not find value, is_code from bytecode table
+        let dest = u64::try_from(dest).unwrap_or(code_len);
+        let mut code_pair = (0u8, false, Value::known(F::zero()));
+        if dest < code_len {
+            // get real value from bytecode table
+            code_pair = code.get_byte_row(dest as usize, region.challenges());
+        }
+
+        self.value
+            .assign(region, offset, Value::known(F::from(code_pair.0 as u64)))?;
+        self.is_code
+            .assign(region, offset, Value::known(F::from(code_pair.1)))?;
+        self.push_rlc.assign(region, offset, code_pair.2)?;
+        self.is_jump_dest.assign(
+            region,
+            offset,
+            F::from(code_pair.0 as u64),
+            F::from(OpcodeId::JUMPDEST.as_u64()),
+        )?;
+
+        Ok(code_pair.1 && code_pair.0 == OpcodeId::JUMPDEST.as_u8())
+    }
+
+    /// The destination word popped from the stack.
+    pub(crate) fn original_word(&self) -> Expression<F> {
+        self.dest.original_word()
+    }
+
+    /// The destination as program counter, only meaningful when valid.
+    pub(crate) fn valid_value(&self) -> Expression<F> {
+        self.dest.valid_value()
+    }
+
+    /// 1 if the destination is valid, 0 otherwise.
+    pub(crate) fn is_valid(&self) -> Expression<F> {
+        and::expr([
+            self.dest.lt_cap(),
+            self.is_code.expr(),
+            self.is_jump_dest.expr(),
+        ])
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct CommonReturnDataCopyGadget<F> {
     is_data_offset_within_u64: IsZeroGadget<F>,
@@ -1852,3 +2084,67 @@ pub(crate) fn get_copy_bytes(
 
     values
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm_circuit::util::math_gadget::test_util::*;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    #[derive(Clone)]
+    /// ShiftAmountTestContainer:
+    /// require(low_byte(a) == LOW_BYTE && is_gte_256(a) == IS_GTE_256)
+    struct ShiftAmountTestContainer<F, const LOW_BYTE: u64, const IS_GTE_256: bool> {
+        shift_amount: ShiftAmountGadget<F>,
+    }
+
+    impl<F: Field, const LOW_BYTE: u64, const IS_GTE_256: bool> MathGadgetContainer<F>
+        for ShiftAmountTestContainer<F, LOW_BYTE, IS_GTE_256>
+    {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let shift_amount = ShiftAmountGadget::construct(cb);
+            cb.require_equal("correct low byte", shift_amount.low_byte(), LOW_BYTE.expr());
+            cb.require_equal(
+                "correct is_gte_256",
+                shift_amount.is_gte_256(),
+                IS_GTE_256.expr(),
+            );
+            cb.require_equal(
+                "is_lt_256 == 1 - is_gte_256",
+                shift_amount.is_lt_256(),
+                1.expr() - IS_GTE_256.expr(),
+            );
+
+            ShiftAmountTestContainer { shift_amount }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[U256],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            self.shift_amount.assign(region, 0, witnesses[0])?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_shift_amount_lt_256() {
+        try_test!(ShiftAmountTestContainer<Fr, 0, false>, [U256::zero()], true);
+        try_test!(ShiftAmountTestContainer<Fr, 1, false>, [U256::one()], true);
+        try_test!(ShiftAmountTestContainer<Fr, 255, false>, [U256::from(255)], true);
+        try_test!(ShiftAmountTestContainer<Fr, 255, true>, [U256::from(255)], false);
+        try_test!(ShiftAmountTestContainer<Fr, 254, false>, [U256::from(255)], false);
+    }
+
+    #[test]
+    fn test_shift_amount_gte_256() {
+        try_test!(ShiftAmountTestContainer<Fr, 0, true>, [U256::from(256)], true);
+        try_test!(ShiftAmountTestContainer<Fr, 0, false>, [U256::from(256)], false);
+        try_test!(ShiftAmountTestContainer<Fr, 8, true>, [U256::from(256 + 8)], true);
+        try_test!(ShiftAmountTestContainer<Fr, 0, true>, [U256::one() << 200], true);
+        try_test!(ShiftAmountTestContainer<Fr, 0, false>, [U256::one() << 200], false);
+        try_test!(ShiftAmountTestContainer<Fr, 255, true>, [U256::MAX], true);
+    }
+}
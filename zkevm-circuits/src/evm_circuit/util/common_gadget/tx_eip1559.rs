@@ -27,7 +27,7 @@ pub(crate) struct TxEip1559Gadget<F> {
     gas_fee_cap: Word<F>,
     // MaxPriorityFeePerGas
     gas_tip_cap: Word<F>,
-    mul_gas_fee_cap_by_gas: MulWordByU64Gadget<F>,
+    mul_gas_fee_cap_by_gas: MulWordByU64Gadget<F, true>,
     balance_check: AddWordsGadget<F, 3, true>,
     // Error condition
     // <https://github.com/ethereum/go-ethereum/blob/master/core/state_transition.go#L241>
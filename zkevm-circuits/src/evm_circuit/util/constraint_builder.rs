@@ -15,7 +15,7 @@ use bus_mapping::{
     state_db::EMPTY_CODE_HASH_LE,
     util::{KECCAK_CODE_HASH_EMPTY, POSEIDON_CODE_HASH_EMPTY},
 };
-use eth_types::{Field, ToLittleEndian, ToScalar, ToWord};
+use eth_types::{evm_types::HardFork, Field, ToLittleEndian, ToScalar, ToWord};
 use gadgets::util::{and, not};
 use halo2_proofs::{
     circuit::Value,
@@ -25,6 +25,10 @@ use halo2_proofs::{
     },
 };
 use itertools::Itertools;
+use strum::IntoEnumIterator;
+
+#[cfg(any(feature = "test", test))]
+use crate::evm_circuit::table::LookupDescriptor;
 
 use super::{rlc, CachedRegion, CellType, StoredExpression};
 
@@ -302,6 +306,7 @@ pub(crate) struct EVMConstraintBuilder<'a, F> {
     pub(crate) curr: Step<F>,
     pub(crate) next: Step<F>,
     challenges: &'a Challenges<Expression<F>>,
+    hard_fork: HardFork,
     execution_state: ExecutionState,
     constraints: Constraints<F>,
     rw_counter_offset: Expression<F>,
@@ -312,6 +317,9 @@ pub(crate) struct EVMConstraintBuilder<'a, F> {
     conditions: Vec<Expression<F>>,
     constraints_location: ConstraintLocation,
     stored_expressions: Vec<StoredExpression<F>>,
+    fixed_table_tags: Vec<FixedTableTag>,
+    #[cfg(any(feature = "test", test))]
+    lookups: Vec<LookupDescriptor>,
     pub(crate) max_inner_degree: (&'static str, usize),
     #[cfg(feature = "debug-annotations")]
     annotations: Vec<String>,
@@ -338,6 +346,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         curr: Step<F>,
         next: Step<F>,
         challenges: &'a Challenges<Expression<F>>,
+        hard_fork: HardFork,
         execution_state: ExecutionState,
     ) -> Self {
         Self {
@@ -345,6 +354,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             curr,
             next,
             challenges,
+            hard_fork,
             execution_state,
             constraints: Constraints {
                 step: Vec::new(),
@@ -360,6 +370,9 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             conditions: Vec::new(),
             constraints_location: ConstraintLocation::Step,
             stored_expressions: Vec::new(),
+            fixed_table_tags: Vec::new(),
+            #[cfg(any(feature = "test", test))]
+            lookups: Vec::new(),
             max_inner_degree: ("", 0),
             annotations: Vec::new(),
         }
@@ -398,6 +411,10 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         self.challenges
     }
 
+    pub(crate) fn hard_fork(&self) -> HardFork {
+        self.hard_fork
+    }
+
     pub(crate) fn execution_state(&self) -> ExecutionState {
         self.execution_state
     }
@@ -1658,6 +1675,15 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     pub(crate) fn add_lookup(&mut self, name: &str, lookup: Lookup<F>) {
+        if let Lookup::Fixed { tag, .. } = &lookup {
+            self.record_fixed_table_tag(tag);
+        }
+        #[cfg(any(feature = "test", test))]
+        self.lookups.push(LookupDescriptor {
+            name: name.to_string(),
+            table: lookup.table(),
+            num_columns: lookup.input_exprs().len(),
+        });
         let lookup = match self.condition_expr_opt() {
             Some(condition) => lookup.conditional(condition),
             None => lookup,
@@ -1670,6 +1696,34 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         self.store_expression(name, compressed_expr, CellType::Lookup(lookup.table()));
     }
 
+    /// Records which fixed table tags the lookup with `tag` may query. A tag
+    /// which is not a constant (e.g. selected by the opcode) may query any of
+    /// them.
+    fn record_fixed_table_tag(&mut self, tag: &Expression<F>) {
+        let tags: Vec<FixedTableTag> = match tag {
+            Expression::Constant(value) => FixedTableTag::iter()
+                .filter(|t| F::from(*t as u64) == *value)
+                .collect(),
+            _ => FixedTableTag::iter().collect(),
+        };
+        for tag in tags {
+            if !self.fixed_table_tags.contains(&tag) {
+                self.fixed_table_tags.push(tag);
+            }
+        }
+    }
+
+    /// Fixed table tags queried by the lookups added so far.
+    pub(crate) fn fixed_table_tags(&self) -> &[FixedTableTag] {
+        &self.fixed_table_tags
+    }
+
+    /// Lookups added so far.
+    #[cfg(any(feature = "test", test))]
+    pub(crate) fn lookups(&self) -> &[LookupDescriptor] {
+        &self.lookups
+    }
+
     pub(crate) fn store_expression(
         &mut self,
         name: &str,
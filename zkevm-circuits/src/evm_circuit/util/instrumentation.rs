@@ -1,10 +1,14 @@
+#[cfg(any(feature = "test", test))]
+use crate::evm_circuit::table::LookupDescriptor;
 use crate::evm_circuit::{
     step::ExecutionState,
-    table::Table,
+    table::{FixedTableTag, Table},
     util::{constraint_builder::EVMConstraintBuilder, CellType},
 };
 use eth_types::Field;
 use itertools::Itertools;
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
 
 type StepSize = Vec<(CellType, ColumnSize)>;
 /// Contains (width, height, num_cells)
@@ -15,6 +19,11 @@ type ColumnSize = (usize, usize, usize);
 pub(crate) struct Instrument {
     // States -> Cell Types -> (width, height, num_cells)
     states: Vec<(ExecutionState, StepSize)>,
+    // States -> Fixed table tags queried by the gadget
+    fixed_table_tags: HashMap<ExecutionState, Vec<FixedTableTag>>,
+    // States -> Lookups added by the gadget
+    #[cfg(any(feature = "test", test))]
+    lookups: HashMap<ExecutionState, Vec<LookupDescriptor>>,
 }
 
 impl Instrument {
@@ -34,6 +43,40 @@ impl Instrument {
             .collect::<Vec<_>>();
 
         self.states.push((execution_state, sizes));
+        self.fixed_table_tags
+            .insert(execution_state, cb.fixed_table_tags().to_vec());
+        #[cfg(any(feature = "test", test))]
+        self.lookups.insert(execution_state, cb.lookups().to_vec());
+    }
+
+    /// Returns the lookups added by the gadget of `execution_state`, in the
+    /// order it added them.
+    #[cfg(any(feature = "test", test))]
+    pub(crate) fn lookups(&self, execution_state: ExecutionState) -> &[LookupDescriptor] {
+        self.lookups
+            .get(&execution_state)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the fixed table tags queried by the gadgets of the given
+    /// `ExecutionState`s, in `FixedTableTag` order.
+    pub(crate) fn fixed_table_tags(
+        &self,
+        execution_states: impl IntoIterator<Item = ExecutionState>,
+    ) -> Vec<FixedTableTag> {
+        let used = execution_states
+            .into_iter()
+            .flat_map(|state| {
+                self.fixed_table_tags
+                    .get(&state)
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect_vec();
+        FixedTableTag::iter()
+            .filter(|tag| used.contains(tag))
+            .collect()
     }
 
     /// Dissasembles the instrumentation data and returns a collection of
@@ -10,6 +10,7 @@ mod byte_size;
 mod cmp_words;
 mod comparison;
 mod constant_division;
+mod constant_divmod;
 mod is_equal;
 mod is_zero;
 mod lt;
@@ -22,8 +23,10 @@ mod mul_word_u64;
 mod pair_select;
 mod range_check;
 mod rlp;
+mod sqrt_floor;
 #[cfg(test)]
 pub mod test_util;
+mod word_repr;
 
 pub(crate) use abs_word::AbsWordGadget;
 pub(crate) use add_words::AddWordsGadget;
@@ -34,6 +37,7 @@ pub(crate) use byte_size::{BitLengthGadget, ByteOrWord, ByteSizeGadget};
 pub(crate) use cmp_words::CmpWordsGadget;
 pub(crate) use comparison::ComparisonGadget;
 pub(crate) use constant_division::ConstantDivisionGadget;
+pub(crate) use constant_divmod::ConstantDivmodGadget;
 pub(crate) use is_equal::IsEqualGadget;
 pub(crate) use is_zero::IsZeroGadget;
 pub(crate) use lt::LtGadget;
@@ -46,6 +50,10 @@ pub(crate) use mul_word_u64::MulWordByU64Gadget;
 pub(crate) use pair_select::PairSelectGadget;
 pub(crate) use range_check::RangeCheckGadget;
 pub(crate) use rlp::ContractCreateGadget;
+#[allow(unused_imports)]
+pub(crate) use sqrt_floor::SqrtFloorGadget;
+#[allow(unused_imports)]
+pub(crate) use word_repr::WordReprConsistencyGadget;
 
 // This function generates a Lagrange polynomial in the range [start, end) which
 // will be evaluated to 1 when `exp == value`, otherwise 0
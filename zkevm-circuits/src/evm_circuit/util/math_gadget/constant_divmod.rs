@@ -0,0 +1,152 @@
+use crate::evm_circuit::util::{
+    constraint_builder::EVMConstraintBuilder, math_gadget::*, CachedRegion,
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Returns (quotient: numerator/D, remainder: numerator%D), with `numerator`
+/// an expression and the divisor `D` fixed at compile time.
+/// Input requirements are the same as `ConstantDivisionGadget`:
+/// - `quotient < 256**N_BYTES`
+/// - `quotient * D < field size`
+/// - `remainder < D` requires a range lookup table for `D`
+#[derive(Clone, Debug)]
+pub struct ConstantDivmodGadget<F, const N_BYTES: usize, const D: u64> {
+    division: ConstantDivisionGadget<F, N_BYTES>,
+}
+
+impl<F: Field, const N_BYTES: usize, const D: u64> ConstantDivmodGadget<F, N_BYTES, D> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, numerator: Expression<F>) -> Self {
+        Self {
+            division: ConstantDivisionGadget::construct(cb, numerator, D),
+        }
+    }
+
+    pub(crate) fn quotient(&self) -> Expression<F> {
+        self.division.quotient()
+    }
+
+    pub(crate) fn remainder(&self) -> Expression<F> {
+        self.division.remainder()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        numerator: u128,
+    ) -> Result<(u128, u128), Error> {
+        self.division.assign(region, offset, numerator)
+    }
+
+    pub(crate) fn assign_value(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        numerator: Value<F>,
+    ) -> Result<Value<(u128, u128)>, Error> {
+        self.division.assign_value(region, offset, numerator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{test_util::*, *};
+    use crate::{
+        evm_circuit::util::{constraint_builder::ConstrainBuilderCommon, Cell},
+        util::Expr,
+    };
+    use eth_types::*;
+    use halo2_proofs::{halo2curves::bn256::Fr, plonk::Error};
+
+    #[derive(Clone)]
+    /// ConstantDivmodTestContainer:
+    /// require(a(N_BYTES) == D * QUOTIENT + REMAINDER)
+    struct ConstantDivmodTestContainer<
+        F,
+        const N_BYTES: usize,
+        const D: u64,
+        const QUOTIENT: u64,
+        const REMAINDER: u64,
+    > {
+        divmod_gadget: ConstantDivmodGadget<F, N_BYTES, D>,
+        a: Cell<F>,
+    }
+
+    impl<
+            F: Field,
+            const N_BYTES: usize,
+            const D: u64,
+            const QUOTIENT: u64,
+            const REMAINDER: u64,
+        > MathGadgetContainer<F>
+        for ConstantDivmodTestContainer<F, N_BYTES, D, QUOTIENT, REMAINDER>
+    {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let a = cb.query_cell();
+            let divmod_gadget = ConstantDivmodGadget::<F, N_BYTES, D>::construct(cb, a.expr());
+
+            cb.require_equal(
+                "correct remainder",
+                divmod_gadget.remainder(),
+                REMAINDER.expr(),
+            );
+            cb.require_equal(
+                "correct quotient",
+                divmod_gadget.quotient(),
+                QUOTIENT.expr(),
+            );
+
+            ConstantDivmodTestContainer { divmod_gadget, a }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let a = u64::from_le_bytes(witnesses[0].to_le_bytes()[..8].try_into().unwrap());
+            let offset = 0;
+
+            self.a.assign(region, offset, Value::known(F::from(a)))?;
+            self.divmod_gadget.assign(region, offset, a as u128)?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_constantdivmodgadget_div32() {
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 32, 0, 0>, [Word::from(0)], true);
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 32, 0, 31>, [Word::from(31)], true);
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 32, 1, 0>, [Word::from(32)], true);
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 32, 1, 1>, [Word::from(33)], true);
+        try_test!(
+            ConstantDivmodTestContainer<Fr, 4, 32, 0x7ffffff, 31>,
+            [Word::from(u32::MAX)],
+            true,
+        );
+        // remainder must be < 32
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 32, 0, 32>, [Word::from(32)], false);
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 32, 1, 31>, [Word::from(31)], false);
+    }
+
+    #[test]
+    fn test_constantdivmodgadget_div64() {
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 64, 0, 0>, [Word::from(0)], true);
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 64, 0, 63>, [Word::from(63)], true);
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 64, 1, 0>, [Word::from(64)], true);
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 64, 1, 1>, [Word::from(65)], true);
+        try_test!(
+            ConstantDivmodTestContainer<Fr, 4, 64, 0x3ffffff, 63>,
+            [Word::from(u32::MAX)],
+            true,
+        );
+        // remainder must be < 64
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 64, 0, 64>, [Word::from(64)], false);
+        try_test!(ConstantDivmodTestContainer<Fr, 4, 64, 1, 63>, [Word::from(63)], false);
+    }
+}
@@ -2,60 +2,88 @@ use crate::{
     evm_circuit::util::{
         self,
         constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-        from_bytes, pow_of_two_expr, split_u256, CachedRegion,
+        from_bytes,
+        math_gadget::IsZeroGadget,
+        not, pow_of_two_expr, split_u256, CachedRegion,
     },
     util::Expr,
 };
-use eth_types::{Field, ToLittleEndian, Word};
+use eth_types::{Field, ToLittleEndian, ToScalar, Word};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
 };
 
-/// Construction of 256-bit product by 256-bit multiplicand * 64-bit multiplier,
-/// which disallows overflow.
+/// Construction of 256-bit product by 256-bit multiplicand * 64-bit multiplier.
+/// With `CHECK_OVERFLOW` the product is required to fit in 256 bits, otherwise
+/// `product` is the lower 256 bits of the full product and `overflow` is set
+/// when the full product doesn't fit in 256 bits.
 #[derive(Clone, Debug)]
-pub(crate) struct MulWordByU64Gadget<F> {
+pub(crate) struct MulWordByU64Gadget<F, const CHECK_OVERFLOW: bool> {
     multiplicand: util::Word<F>,
     product: util::Word<F>,
     carry_lo: [util::Cell<F>; 8],
+    // `multiplicand_hi ⋅ multiplier + carry_lo < 2^192`, so 8 bytes are enough
+    carry_hi: Option<[util::Cell<F>; 8]>,
+    carry_hi_is_zero: Option<IsZeroGadget<F>>,
 }
 
-impl<F: Field> MulWordByU64Gadget<F> {
+impl<F: Field, const CHECK_OVERFLOW: bool> MulWordByU64Gadget<F, CHECK_OVERFLOW> {
     pub(crate) fn construct(
         cb: &mut EVMConstraintBuilder<F>,
         multiplicand: util::Word<F>,
         multiplier: Expression<F>,
     ) -> Self {
-        let gadget = Self {
-            multiplicand,
-            product: cb.query_word_rlc(),
-            carry_lo: cb.query_bytes(),
+        let product = cb.query_word_rlc();
+        let carry_lo = cb.query_bytes();
+        let carry_hi = if CHECK_OVERFLOW {
+            None
+        } else {
+            Some(cb.query_bytes())
         };
 
-        let multiplicand_lo = from_bytes::expr(&gadget.multiplicand.cells[..16]);
-        let multiplicand_hi = from_bytes::expr(&gadget.multiplicand.cells[16..]);
+        let multiplicand_lo = from_bytes::expr(&multiplicand.cells[..16]);
+        let multiplicand_hi = from_bytes::expr(&multiplicand.cells[16..]);
 
-        let product_lo = from_bytes::expr(&gadget.product.cells[..16]);
-        let product_hi = from_bytes::expr(&gadget.product.cells[16..]);
+        let product_lo = from_bytes::expr(&product.cells[..16]);
+        let product_hi = from_bytes::expr(&product.cells[16..]);
 
-        let carry_lo = from_bytes::expr(&gadget.carry_lo[..8]);
+        let carry_lo_expr = from_bytes::expr(&carry_lo[..8]);
 
         cb.require_equal(
             "multiplicand_lo ⋅ multiplier == carry_lo ⋅ 2^128 + product_lo",
             multiplicand_lo * multiplier.expr(),
-            carry_lo.clone() * pow_of_two_expr(128) + product_lo,
+            carry_lo_expr.clone() * pow_of_two_expr(128) + product_lo,
         );
 
-        cb.require_equal(
-            "multiplicand_hi ⋅ multiplier + carry_lo == product_hi",
-            multiplicand_hi * multiplier.expr() + carry_lo,
-            product_hi,
-        );
+        let carry_hi_is_zero = if let Some(carry_hi) = carry_hi.as_ref() {
+            let carry_hi = from_bytes::expr(&carry_hi[..8]);
+            cb.require_equal(
+                "multiplicand_hi ⋅ multiplier + carry_lo == carry_hi ⋅ 2^128 + product_hi",
+                multiplicand_hi * multiplier + carry_lo_expr,
+                carry_hi.clone() * pow_of_two_expr(128) + product_hi,
+            );
+            Some(IsZeroGadget::construct(cb, carry_hi))
+        } else {
+            cb.require_equal(
+                "multiplicand_hi ⋅ multiplier + carry_lo == product_hi",
+                multiplicand_hi * multiplier + carry_lo_expr,
+                product_hi,
+            );
+            None
+        };
 
-        gadget
+        Self {
+            multiplicand,
+            product,
+            carry_lo,
+            carry_hi,
+            carry_hi_is_zero,
+        }
     }
 
+    /// Assigns the gadget, where `product` is the lower 256 bits of
+    /// `multiplicand ⋅ multiplier`.
     pub(crate) fn assign(
         &self,
         region: &mut CachedRegion<'_, '_, F>,
@@ -69,22 +97,54 @@ impl<F: Field> MulWordByU64Gadget<F> {
         self.product
             .assign(region, offset, Some(product.to_le_bytes()))?;
 
-        let (multiplicand_lo, _) = split_u256(&multiplicand);
-        let (product_lo, _) = split_u256(&product);
+        let (multiplicand_lo, multiplicand_hi) = split_u256(&multiplicand);
+        let (product_lo, product_hi) = split_u256(&product);
 
         let carry_lo = (multiplicand_lo * multiplier - product_lo) >> 128;
-        for (cell, byte) in self.carry_lo.iter().zip(
-            u64::try_from(carry_lo)
-                .map_err(|_| Error::Synthesis)?
-                .to_le_bytes()
-                .iter(),
-        ) {
-            cell.assign(region, offset, Value::known(F::from(*byte as u64)))?;
+        let carry_hi = if CHECK_OVERFLOW {
+            Word::zero()
+        } else {
+            (multiplicand_hi * multiplier + carry_lo - product_hi) >> 128
+        };
+        for (carry_cells, carry) in [
+            Some((&self.carry_lo, carry_lo)),
+            self.carry_hi
+                .as_ref()
+                .map(|carry_hi_cells| (carry_hi_cells, carry_hi)),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            for (cell, byte) in carry_cells.iter().zip(
+                u64::try_from(carry)
+                    .map_err(|_| Error::Synthesis)?
+                    .to_le_bytes()
+                    .iter(),
+            ) {
+                cell.assign(region, offset, Value::known(F::from(*byte as u64)))?;
+            }
+        }
+        if let Some(carry_hi_is_zero) = self.carry_hi_is_zero.as_ref() {
+            carry_hi_is_zero.assign(
+                region,
+                offset,
+                carry_hi
+                    .to_scalar()
+                    .expect("unexpected U256 -> Scalar conversion failure"),
+            )?;
         }
 
         Ok(())
     }
 
+    /// Returns 1 when the full product doesn't fit in 256 bits, which can only
+    /// happen when overflow isn't checked.
+    pub(crate) fn overflow(&self) -> Expression<F> {
+        self.carry_hi_is_zero
+            .as_ref()
+            .map_or(0.expr(), |is_zero| not::expr(is_zero.expr()))
+    }
+
     pub(crate) fn product(&self) -> &util::Word<F> {
         &self.product
     }
@@ -94,13 +154,13 @@ impl<F: Field> MulWordByU64Gadget<F> {
 mod tests {
     use super::{super::test_util::*, *};
     use crate::evm_circuit::util::Cell;
-    use eth_types::Word;
+    use eth_types::{Word, U256};
     use halo2_proofs::{halo2curves::bn256::Fr, plonk::Error};
 
     #[derive(Clone)]
     /// MulWordByU64TestContainer: require(product = a*(b as u64))
     struct MulWordByU64TestContainer<F> {
-        mulwords_u64_gadget: MulWordByU64Gadget<F>,
+        mulwords_u64_gadget: MulWordByU64Gadget<F, true>,
         a: util::Word<F>,
         b: Cell<F>,
         product: util::Word<F>,
@@ -111,7 +171,7 @@ mod tests {
             let a = cb.query_word_rlc();
             let b = cb.query_cell();
             let product = cb.query_word_rlc();
-            let mulwords_u64_gadget = MulWordByU64Gadget::<F>::construct(cb, a.clone(), b.expr());
+            let mulwords_u64_gadget = MulWordByU64Gadget::construct(cb, a.clone(), b.expr());
             MulWordByU64TestContainer {
                 mulwords_u64_gadget,
                 a,
@@ -140,6 +200,52 @@ mod tests {
         }
     }
 
+    #[derive(Clone)]
+    /// MulWordByU64OverflowTestContainer:
+    /// require(product = a*(b as u64) % 2^256 && overflow == OVERFLOW)
+    struct MulWordByU64OverflowTestContainer<F, const OVERFLOW: bool> {
+        mulwords_u64_gadget: MulWordByU64Gadget<F, false>,
+        a: util::Word<F>,
+        b: Cell<F>,
+    }
+
+    impl<F: Field, const OVERFLOW: bool> MathGadgetContainer<F>
+        for MulWordByU64OverflowTestContainer<F, OVERFLOW>
+    {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let a = cb.query_word_rlc();
+            let b = cb.query_cell();
+            let mulwords_u64_gadget = MulWordByU64Gadget::construct(cb, a.clone(), b.expr());
+            cb.require_equal(
+                "correct overflow",
+                mulwords_u64_gadget.overflow(),
+                OVERFLOW.expr(),
+            );
+            MulWordByU64OverflowTestContainer {
+                mulwords_u64_gadget,
+                a,
+                b,
+            }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let a = witnesses[0];
+            let b = u64::from_le_bytes(witnesses[1].to_le_bytes()[..8].try_into().unwrap());
+            let product = witnesses[2];
+            let offset = 0;
+
+            self.a.assign(region, offset, Some(a.to_le_bytes()))?;
+            self.b.assign(region, offset, Value::known(F::from(b)))?;
+            self.mulwords_u64_gadget.assign(region, 0, a, b, product)?;
+
+            Ok(())
+        }
+    }
+
     #[test]
     fn test_mulwordu64_expect() {
         // 0 * 0 = 0
@@ -184,6 +290,12 @@ mod tests {
             [WORD_LOW_MAX, Word::from(2), WORD_LOW_MAX << 1],
             true,
         );
+        // (max / u64_max) * u64_max = max
+        try_test!(
+            MulWordByU64TestContainer<Fr>,
+            [Word::MAX / u64::MAX, Word::from(u64::MAX), Word::MAX],
+            true,
+        );
     }
 
     #[test]
@@ -200,4 +312,52 @@ mod tests {
             false,
         );
     }
+
+    #[test]
+    fn test_mulwordu64_overflow() {
+        // (max / u64_max) * u64_max = max, just no overflow
+        try_test!(
+            MulWordByU64OverflowTestContainer<Fr, false>,
+            [Word::MAX / u64::MAX, Word::from(u64::MAX), Word::MAX],
+            true,
+        );
+        // (max / u64_max + 1) * u64_max = 2^256 + u64_max - 1, just overflow
+        try_test!(
+            MulWordByU64OverflowTestContainer<Fr, true>,
+            [
+                Word::MAX / u64::MAX + 1,
+                Word::from(u64::MAX),
+                Word::from(u64::MAX - 1)
+            ],
+            true,
+        );
+        // high_max * 2 = high_max << 1 + 2^256
+        try_test!(
+            MulWordByU64OverflowTestContainer<Fr, true>,
+            [WORD_HIGH_MAX, Word::from(2), WORD_HIGH_MAX << 1],
+            true,
+        );
+        // max * u64_max = 2^256 * (u64_max - 1) + 2^256 - u64_max
+        try_test!(
+            MulWordByU64OverflowTestContainer<Fr, true>,
+            [
+                Word::MAX,
+                Word::from(u64::MAX),
+                Word::MAX - U256::from(u64::MAX) + 1
+            ],
+            true,
+        );
+        // the overflow flag can't be dropped
+        try_test!(
+            MulWordByU64OverflowTestContainer<Fr, false>,
+            [WORD_HIGH_MAX, Word::from(2), WORD_HIGH_MAX << 1],
+            false,
+        );
+        // and can't be set without an overflow
+        try_test!(
+            MulWordByU64OverflowTestContainer<Fr, true>,
+            [Word::from(22222), Word::from(500), Word::from(11111000)],
+            false,
+        );
+    }
 }
@@ -0,0 +1,179 @@
+use crate::{
+    evm_circuit::{
+        param::MAX_N_BYTES_INTEGER,
+        util::{
+            constraint_builder::EVMConstraintBuilder, math_gadget::*, transpose_val_ret,
+            CachedRegion, Cell,
+        },
+    },
+    util::Expr,
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Returns `root = floor(sqrt(x))`, with `x` an expression.
+/// The equations that are enforced are `x - root^2 == diff` and
+/// `2 * root - diff == slack`, with `root`, `diff` and `slack` all
+/// `< 256**N_BYTES`, which is equivalent to `root^2 <= x < (root + 1)^2`.
+/// Input requirements:
+/// - `x < 256**N_BYTES`
+/// - `N_BYTES * 2 <= MAX_N_BYTES_INTEGER` so that `root^2` can't overflow
+#[derive(Clone, Debug)]
+pub struct SqrtFloorGadget<F, const N_BYTES: usize> {
+    root: Cell<F>,
+    root_range_check: RangeCheckGadget<F, N_BYTES>,
+    // `x - root^2`, which is `>= 0` iff `root^2 <= x`
+    diff_range_check: RangeCheckGadget<F, N_BYTES>,
+    // `2 * root - diff`, which is `>= 0` iff `x < (root + 1)^2`
+    slack_range_check: RangeCheckGadget<F, N_BYTES>,
+}
+
+impl<F: Field, const N_BYTES: usize> SqrtFloorGadget<F, N_BYTES> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, x: Expression<F>) -> Self {
+        assert!(N_BYTES * 2 <= MAX_N_BYTES_INTEGER);
+        let root = cb.query_cell();
+
+        let root_range_check = RangeCheckGadget::construct(cb, root.expr());
+        let diff = x - root.expr() * root.expr();
+        let diff_range_check = RangeCheckGadget::construct(cb, diff.clone());
+        let slack_range_check = RangeCheckGadget::construct(cb, 2.expr() * root.expr() - diff);
+
+        Self {
+            root,
+            root_range_check,
+            diff_range_check,
+            slack_range_check,
+        }
+    }
+
+    pub(crate) fn expr(&self) -> Expression<F> {
+        self.root.expr()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        x: u128,
+    ) -> Result<u128, Error> {
+        let root = sqrt_floor(x);
+        let diff = x - root * root;
+        let slack = 2 * root - diff;
+
+        self.root
+            .assign(region, offset, Value::known(F::from_u128(root)))?;
+        self.root_range_check
+            .assign(region, offset, F::from_u128(root))?;
+        self.diff_range_check
+            .assign(region, offset, F::from_u128(diff))?;
+        self.slack_range_check
+            .assign(region, offset, F::from_u128(slack))?;
+
+        Ok(root)
+    }
+
+    pub(crate) fn assign_value(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        x: Value<F>,
+    ) -> Result<Value<u128>, Error> {
+        transpose_val_ret(x.map(|x| self.assign(region, offset, x.get_lower_128())))
+    }
+}
+
+/// Returns `floor(sqrt(x))`, computed bit by bit from the most significant
+/// one.
+fn sqrt_floor(x: u128) -> u128 {
+    let mut root = 0u128;
+    for shift in (0..64).rev() {
+        let candidate = root | (1 << shift);
+        if candidate * candidate <= x {
+            root = candidate;
+        }
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{test_util::*, *};
+    use eth_types::*;
+    use halo2_proofs::{halo2curves::bn256::Fr, plonk::Error};
+
+    #[derive(Clone)]
+    /// SqrtFloorTestContainer: require(floor(sqrt(a)) == ROOT)
+    struct SqrtFloorTestContainer<F, const N_BYTES: usize, const ROOT: u64> {
+        sqrt_floor_gadget: SqrtFloorGadget<F, N_BYTES>,
+        a: Cell<F>,
+    }
+
+    impl<F: Field, const N_BYTES: usize, const ROOT: u64> MathGadgetContainer<F>
+        for SqrtFloorTestContainer<F, N_BYTES, ROOT>
+    {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let a = cb.query_cell();
+            let sqrt_floor_gadget = SqrtFloorGadget::<F, N_BYTES>::construct(cb, a.expr());
+            cb.require_equal("correct root", sqrt_floor_gadget.expr(), ROOT.expr());
+            SqrtFloorTestContainer {
+                sqrt_floor_gadget,
+                a,
+            }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let a = u64::from_le_bytes(witnesses[0].to_le_bytes()[..8].try_into().unwrap());
+            let offset = 0;
+
+            self.a.assign(region, offset, Value::known(F::from(a)))?;
+            self.sqrt_floor_gadget.assign(region, offset, a as u128)?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sqrt_floor_values() {
+        for (x, root) in [(0, 0), (1, 1), (2, 1), (3, 1), (4, 2), (99, 9), (100, 10)] {
+            assert_eq!(sqrt_floor(x), root);
+        }
+        assert_eq!(sqrt_floor(u64::MAX as u128), u32::MAX as u128);
+    }
+
+    #[test]
+    fn test_sqrtfloorgadget_zero() {
+        try_test!(SqrtFloorTestContainer<Fr, 4, 0>, [Word::from(0)], true);
+        try_test!(SqrtFloorTestContainer<Fr, 4, 1>, [Word::from(0)], false);
+    }
+
+    #[test]
+    fn test_sqrtfloorgadget_perfect_square() {
+        try_test!(SqrtFloorTestContainer<Fr, 4, 1>, [Word::from(1)], true);
+        try_test!(SqrtFloorTestContainer<Fr, 4, 12>, [Word::from(144)], true);
+        try_test!(
+            SqrtFloorTestContainer<Fr, 8, 4294967295>,
+            [Word::from(4294967295u64 * 4294967295u64)],
+            true,
+        );
+        try_test!(SqrtFloorTestContainer<Fr, 4, 11>, [Word::from(144)], false);
+    }
+
+    #[test]
+    fn test_sqrtfloorgadget_non_square() {
+        try_test!(SqrtFloorTestContainer<Fr, 4, 1>, [Word::from(3)], true);
+        try_test!(SqrtFloorTestContainer<Fr, 4, 12>, [Word::from(168)], true);
+        try_test!(
+            SqrtFloorTestContainer<Fr, 8, 4294967295>,
+            [Word::from(u64::MAX)],
+            true,
+        );
+        try_test!(SqrtFloorTestContainer<Fr, 4, 13>, [Word::from(168)], false);
+    }
+}
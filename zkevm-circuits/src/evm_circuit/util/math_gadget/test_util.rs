@@ -31,7 +31,7 @@ use halo2_proofs::plonk::SecondPhase;
 #[cfg(not(feature = "onephase"))]
 use halo2_proofs::plonk::ThirdPhase;
 
-use eth_types::{Field, Word, U256};
+use eth_types::{evm_types::HardFork, Field, Word, U256};
 pub(crate) use halo2_proofs::circuit::{Layouter, Value};
 use halo2_proofs::{
     circuit::SimpleFloorPlanner,
@@ -143,6 +143,7 @@ impl<F: Field, G: MathGadgetContainer<F>> Circuit<F> for UnitTestMathGadgetBaseC
             step_curr.clone(),
             step_next,
             &challenges_exprs,
+            HardFork::default(),
             ExecutionState::STOP,
         );
         let math_gadget_container = G::configure_gadget_container(&mut cb);
@@ -0,0 +1,138 @@
+use crate::{
+    evm_circuit::util::{
+        self,
+        constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+        from_bytes, split_u256, CachedRegion, Cell,
+    },
+    util::Expr,
+};
+use eth_types::{Field, ToScalar, Word};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Constrains the two 128-bit limbs `(lo, hi)` of a 256-bit value to match the
+/// value of an RLC encoded `util::Word`, so that gadgets using either
+/// representation can be connected.
+/// The equations that are enforced are `lo == from_bytes(word[0..16])` and
+/// `hi == from_bytes(word[16..32])`. The bytes of `word` are range checked
+/// already, so the limbs are `< 2**128`.
+#[derive(Clone, Debug)]
+pub struct WordReprConsistencyGadget<F> {
+    lo: Cell<F>,
+    hi: Cell<F>,
+}
+
+impl<F: Field> WordReprConsistencyGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, word: &util::Word<F>) -> Self {
+        let lo = cb.query_cell();
+        let hi = cb.query_cell();
+
+        cb.require_equal(
+            "lo == from_bytes(word[0..16])",
+            lo.expr(),
+            from_bytes::expr(&word.cells[..16]),
+        );
+        cb.require_equal(
+            "hi == from_bytes(word[16..32])",
+            hi.expr(),
+            from_bytes::expr(&word.cells[16..]),
+        );
+
+        Self { lo, hi }
+    }
+
+    /// Returns the `[lo, hi]` limbs.
+    pub(crate) fn limbs(&self) -> [Expression<F>; 2] {
+        [self.lo.expr(), self.hi.expr()]
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        value: Word,
+    ) -> Result<(), Error> {
+        let (lo, hi) = split_u256(&value);
+        for (cell, limb) in [(&self.lo, lo), (&self.hi, hi)] {
+            cell.assign(
+                region,
+                offset,
+                Value::known(
+                    limb.to_scalar()
+                        .expect("unexpected U256 -> Scalar conversion failure"),
+                ),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::test_util::*, *};
+    use eth_types::ToLittleEndian;
+    use halo2_proofs::{halo2curves::bn256::Fr, plonk::Error};
+
+    #[derive(Clone)]
+    /// WordReprConsistencyTestContainer: require(a == limbs(b))
+    struct WordReprConsistencyTestContainer<F> {
+        word_repr_gadget: WordReprConsistencyGadget<F>,
+        a: util::Word<F>,
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for WordReprConsistencyTestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let a = cb.query_word_rlc();
+            let word_repr_gadget = WordReprConsistencyGadget::construct(cb, &a);
+            WordReprConsistencyTestContainer {
+                word_repr_gadget,
+                a,
+            }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let offset = 0;
+            self.a
+                .assign(region, offset, Some(witnesses[0].to_le_bytes()))?;
+            self.word_repr_gadget.assign(region, offset, witnesses[1])?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_word_repr_consistent() {
+        for value in [
+            Word::zero(),
+            Word::from(0x1234u64),
+            Word::from(u128::MAX),
+            Word::from(u128::MAX) + 1,
+            Word::MAX,
+        ] {
+            try_test!(WordReprConsistencyTestContainer<Fr>, [value, value], true);
+        }
+    }
+
+    #[test]
+    fn test_word_repr_inconsistent() {
+        // differing lo limbs
+        try_test!(
+            WordReprConsistencyTestContainer<Fr>,
+            [Word::from(1), Word::from(2)],
+            false,
+        );
+        // differing hi limbs
+        try_test!(
+            WordReprConsistencyTestContainer<Fr>,
+            [Word::MAX, Word::from(u128::MAX)],
+            false,
+        );
+    }
+}
@@ -10,7 +10,7 @@ use crate::{
             constraint_builder::EVMConstraintBuilder,
             from_bytes,
             math_gadget::{
-                AddWordsGadget, ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
+                AddWordsGadget, ConstantDivmodGadget, IsZeroGadget, LtGadget, MinMaxGadget,
                 RangeCheckGadget,
             },
             not, or, select, sum, Cell, CellType, MemoryAddress,
@@ -345,12 +345,12 @@ impl<F: Field> MemoryExpandedAddressGadget<F> {
 /// `memory_word_size = ceil(address/32) = floor((address + 31) / 32)`
 #[derive(Clone, Debug)]
 pub(crate) struct MemoryWordSizeGadget<F> {
-    memory_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_word_size: ConstantDivmodGadget<F, N_BYTES_MEMORY_WORD_SIZE, 32>,
 }
 
 impl<F: Field> MemoryWordSizeGadget<F> {
     pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, address: Expression<F>) -> Self {
-        let memory_word_size = ConstantDivisionGadget::construct(cb, address + 31.expr(), 32);
+        let memory_word_size = ConstantDivmodGadget::construct(cb, address + 31.expr());
 
         Self { memory_word_size }
     }
@@ -680,6 +680,22 @@ impl<F: Field> MemoryMask<F> {
     }
 }
 
+/// Returns the gas cost of expanding the memory from `curr_memory_word_size`
+/// to `next_memory_word_size` words, which is the difference between the next
+/// and current memory costs:
+/// `memory_cost = Gmem * memory_word_size + floor(memory_word_size *
+/// memory_word_size / 512)`
+/// It is zero if the memory is not expanded.
+pub(crate) fn memory_expansion_cost(curr_memory_word_size: u64, next_memory_word_size: u64) -> u64 {
+    let memory_cost = |memory_word_size: u64| {
+        let quad_memory_cost = (memory_word_size as u128) * (memory_word_size as u128)
+            / GasCost::MEMORY_EXPANSION_QUAD_DENOMINATOR.as_u64() as u128;
+        GasCost::MEMORY_EXPANSION_LINEAR_COEFF.as_u64() * memory_word_size + quad_memory_cost as u64
+    };
+    memory_cost(next_memory_word_size.max(curr_memory_word_size))
+        - memory_cost(curr_memory_word_size)
+}
+
 /// Returns (new memory size, memory gas cost) for a memory access.
 /// If the memory needs to be expanded this will result in an extra gas cost.
 /// This gas cost is the difference between the next and current memory costs:
@@ -689,8 +705,16 @@ impl<F: Field> MemoryMask<F> {
 pub(crate) struct MemoryExpansionGadget<F, const N: usize, const N_BYTES_MEMORY_WORD_SIZE: usize> {
     memory_word_sizes: [MemoryWordSizeGadget<F>; N],
     max_memory_word_sizes: [MinMaxGadget<F, N_BYTES_MEMORY_WORD_SIZE>; N],
-    curr_quad_memory_cost: ConstantDivisionGadget<F, N_BYTES_GAS>,
-    next_quad_memory_cost: ConstantDivisionGadget<F, N_BYTES_GAS>,
+    curr_quad_memory_cost: ConstantDivmodGadget<
+        F,
+        N_BYTES_GAS,
+        { GasCost::MEMORY_EXPANSION_QUAD_DENOMINATOR.as_u64() },
+    >,
+    next_quad_memory_cost: ConstantDivmodGadget<
+        F,
+        N_BYTES_GAS,
+        { GasCost::MEMORY_EXPANSION_QUAD_DENOMINATOR.as_u64() },
+    >,
     next_memory_word_size: Expression<F>,
     gas_cost: Expression<F>,
 }
@@ -731,15 +755,13 @@ impl<F: Field, const N: usize, const N_BYTES_MEMORY_WORD_SIZE: usize>
         // Calculate the quad memory cost for the current and next memory size.
         // These quad costs will also be range limited to `<
         // 256**MAX_QUAD_COST_IN_BYTES`.
-        let curr_quad_memory_cost = ConstantDivisionGadget::construct(
+        let curr_quad_memory_cost = ConstantDivmodGadget::construct(
             cb,
             curr_memory_word_size.clone() * curr_memory_word_size.clone(),
-            GasCost::MEMORY_EXPANSION_QUAD_DENOMINATOR.as_u64(),
         );
-        let next_quad_memory_cost = ConstantDivisionGadget::construct(
+        let next_quad_memory_cost = ConstantDivmodGadget::construct(
             cb,
             next_memory_word_size.clone() * next_memory_word_size.clone(),
-            GasCost::MEMORY_EXPANSION_QUAD_DENOMINATOR.as_u64(),
         );
 
         // Calculate the gas cost for the memory expansion.
@@ -817,6 +839,10 @@ impl<F: Field, const N: usize, const N_BYTES_MEMORY_WORD_SIZE: usize>
         let memory_cost = GasCost::MEMORY_EXPANSION_LINEAR_COEFF.as_u64()
             * (next_memory_word_size - curr_memory_word_size)
             + (next_quad_memory_cost - curr_quad_memory_cost) as u64;
+        debug_assert_eq!(
+            memory_cost,
+            memory_expansion_cost(curr_memory_word_size, next_memory_word_size)
+        );
 
         // Return the new memory size and the memory expansion gas cost
         Ok((next_memory_word_size, memory_cost))
@@ -1006,3 +1032,103 @@ impl<F: Field, const MAX_BYTES: usize, const ADDR_SIZE_IN_BYTES: usize>
         self.selectors[idx].expr()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm_circuit::util::math_gadget::test_util::*;
+    use eth_types::Word;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    #[derive(Clone)]
+    /// MemoryExpansionTestContainer: require(gas_cost == expected_gas_cost)
+    /// when expanding the memory to cover `address`.
+    struct MemoryExpansionTestContainer<F> {
+        memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+        curr_memory_word_size: Cell<F>,
+        address: Cell<F>,
+        expected_gas_cost: Cell<F>,
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for MemoryExpansionTestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let curr_memory_word_size = cb.curr.state.memory_word_size.clone();
+            let address = cb.query_cell();
+            let expected_gas_cost = cb.query_cell();
+            let memory_expansion = MemoryExpansionGadget::construct(cb, [address.expr()]);
+            cb.require_equal(
+                "gas_cost == expected_gas_cost",
+                memory_expansion.gas_cost(),
+                expected_gas_cost.expr(),
+            );
+
+            MemoryExpansionTestContainer {
+                memory_expansion,
+                curr_memory_word_size,
+                address,
+                expected_gas_cost,
+            }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let curr_memory_word_size = witnesses[0].as_u64();
+            let address = witnesses[1].as_u64();
+            let offset = 0;
+
+            self.curr_memory_word_size.assign(
+                region,
+                offset,
+                Value::known(F::from(curr_memory_word_size)),
+            )?;
+            self.address
+                .assign(region, offset, Value::known(F::from(address)))?;
+            let (next_memory_word_size, gas_cost) =
+                self.memory_expansion
+                    .assign(region, offset, curr_memory_word_size, [address])?;
+            let expected_gas_cost =
+                memory_expansion_cost(curr_memory_word_size, next_memory_word_size);
+            assert_eq!(gas_cost, expected_gas_cost);
+            self.expected_gas_cost.assign(
+                region,
+                offset,
+                Value::known(F::from(expected_gas_cost)),
+            )?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn memory_expansion_cost_values() {
+        assert_eq!(memory_expansion_cost(0, 0), 0);
+        assert_eq!(memory_expansion_cost(0, 1), 3);
+        assert_eq!(memory_expansion_cost(0, 32), 3 * 32 + 2);
+        assert_eq!(memory_expansion_cost(32, 32), 0);
+        assert_eq!(memory_expansion_cost(32, 1), 0);
+        assert_eq!(memory_expansion_cost(1, 1024), 3 * 1023 + 1024 * 1024 / 512);
+    }
+
+    #[test]
+    fn test_memory_expansion_cost_matches_gadget() {
+        for (curr_memory_word_size, address) in [
+            (0u64, 0u64),
+            (0, 1),
+            (0, 32),
+            (0, 33),
+            (1, 31),
+            (2, 0x100),
+            (0x10, 0x1000),
+            (0x100, 0x8000),
+        ] {
+            try_test!(
+                MemoryExpansionTestContainer<Fr>,
+                [Word::from(curr_memory_word_size), Word::from(address)],
+                true,
+            );
+        }
+    }
+}
@@ -150,3 +150,19 @@ fn variadic_size_check() {
     assert_eq!(prover1.fixed(), prover2.fixed());
     assert_eq!(prover1.permutation(), prover2.permutation());
 }
+
+#[test]
+fn exp_circuit_min_num_rows() {
+    use crate::exp_circuit::param::{OFFSET_INCREMENT, UNUSABLE_EXP_ROWS};
+
+    // 7 = 0b111 takes 2 squarings and 2 multiplications, 8 = 0b1000 only 3
+    // squarings.
+    let code = gen_code_multiple(vec![(3.into(), 7.into()), (5.into(), 8.into())]);
+    let builder = gen_data(code);
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+    assert_eq!(block.exp_events.len(), 2);
+
+    let (num_rows, max_rows) = ExpCircuit::<Fr>::min_num_rows_block(&block);
+    assert_eq!(num_rows, (4 + 3) * OFFSET_INCREMENT + UNUSABLE_EXP_ROWS);
+    assert_eq!(max_rows, block.circuits_params.max_exp_steps);
+}
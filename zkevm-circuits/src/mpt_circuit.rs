@@ -45,6 +45,16 @@ pub struct MptCircuit<F: Field> {
     _phantom: std::marker::PhantomData<F>,
 }
 
+impl<F: Field> MptCircuit<F> {
+    /// Return the state root after the last mpt update proven by the circuit,
+    /// or `None` if the updates carry no zktrie witness (e.g. mocked state
+    /// roots) or the last trace has no valid root after the update.
+    pub fn final_state_root(&self) -> Option<F> {
+        let trace = self.mpt_updates.smt_traces.last()?;
+        Option::from(F::from_repr(trace.account_path.get(1)?.root.0))
+    }
+}
+
 /// Circuit configuration argument ts
 pub struct MptCircuitConfigArgs {
     /// PoseidonTable
@@ -213,3 +223,64 @@ impl Circuit<Fr> for MptCircuit<Fr> {
         self.synthesize_sub(&mpt_config, &challenges, &mut layouter)
     }
 }
+
+#[cfg(all(test, feature = "scroll"))]
+mod tests {
+    use super::*;
+    use crate::witness::block_convert;
+    use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
+    use eth_types::{bytecode, geth_types::GethData, ToScalar};
+    use halo2_proofs::halo2curves::group::ff::PrimeField;
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
+
+    #[test]
+    fn mpt_circuit_final_state_root() {
+        let code = bytecode! {
+            .op_sstore(0x00, 0x01)
+            .op_sstore(0x01, 0x02)
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            CircuitsParams {
+                max_mpt_rows: 3500,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let mut block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+        // Mocked state roots come without zktrie traces.
+        assert_eq!(MptCircuit::new_from_block(&block).final_state_root(), None);
+
+        block.mpt_updates.mock_fill_state_roots();
+        let trace = block.mpt_updates.smt_traces.last().unwrap();
+        let expected = Fr::from_repr(trace.account_path[1].root.0).unwrap();
+        assert_eq!(
+            Some(expected),
+            block.mpt_updates.new_root().to_scalar(),
+            "the last trace must end at the new root of the updates"
+        );
+        let mpt_circuit = MptCircuit::new_from_block(&block);
+        assert_eq!(mpt_circuit.final_state_root(), Some(expected));
+
+        // A root which is not a field element can't be returned.
+        let trace = block.mpt_updates.smt_traces.last_mut().unwrap();
+        trace.account_path[1].root.0 = [0xff; 32];
+        assert_eq!(MptCircuit::new_from_block(&block).final_state_root(), None);
+    }
+}
@@ -1,6 +1,6 @@
 //! wrapping of mpt-circuit
 use crate::{
-    bytecode_circuit::bytecode_unroller::HASHBLOCK_BYTES_IN_FIELD,
+    bytecode_circuit::bytecode_unroller::{unroll_to_hash_input_default, HASHBLOCK_BYTES_IN_FIELD},
     table::PoseidonTable,
     util::{Challenges, SubCircuit, SubCircuitConfig},
     witness::{self},
@@ -12,10 +12,43 @@ use halo2_proofs::{
     plonk::{Circuit, ConstraintSystem, Error},
 };
 use hash_circuit::hash::{Hashable, PoseidonHashChip, PoseidonHashConfig, PoseidonHashTable};
+use std::marker::PhantomData;
 
-/// re-wrapping for mpt circuit
+/// Turns the bytes of a bytecode into the field element inputs whose sponge
+/// gives its code hash, see [`PoseidonCircuit`].
+pub trait CodeHasher<F: Field> {
+    /// Return the hash inputs of `code`, each absorbing
+    /// `PoseidonTable::INPUT_WIDTH` field elements.
+    fn hash_inputs(code: &[u8]) -> Vec<[F; PoseidonTable::INPUT_WIDTH]>;
+}
+
+/// The poseidon code hash, which packs the bytes in the same way as the
+/// bytecode circuit.
+#[derive(Default, Clone, Debug)]
+pub struct PoseidonCodeHasher;
+
+impl<F: Field> CodeHasher<F> for PoseidonCodeHasher {
+    fn hash_inputs(code: &[u8]) -> Vec<[F; PoseidonTable::INPUT_WIDTH]> {
+        unroll_to_hash_input_default::<F>(code.iter().copied())
+    }
+}
+
+/// re-wrapping for mpt circuit, hashing `STEP` bytes per poseidon hash block
+///
+/// The hashes loaded into the poseidon table depend on the enabled features:
+/// - `zktrie`: the node, leaf and key hashes of the zktrie proofs
+/// - `poseidon-codehash`: the poseidon code hash of every non-empty bytecode
+///
+/// The two features are independent, with both of them the table holds the
+/// union of the hashes, and without any of them the table is left empty.
+///
+/// The bytecodes are turned into hash inputs by `H`.
 #[derive(Default, Clone, Debug)]
-pub struct PoseidonCircuit<F: Field>(pub(crate) PoseidonHashTable<F>, usize);
+pub struct PoseidonCircuit<
+    F: Field,
+    const STEP: usize = HASH_BLOCK_STEP_SIZE,
+    H = PoseidonCodeHasher,
+>(pub(crate) PoseidonHashTable<F>, usize, PhantomData<H>);
 
 /// Circuit configuration argument ts
 pub struct PoseidonCircuitConfigArgs {
@@ -23,13 +56,17 @@ pub struct PoseidonCircuitConfigArgs {
     pub poseidon_table: PoseidonTable,
 }
 
-/// re-wrapping for poseidon config
+/// re-wrapping for poseidon config, carrying the number of bytes `STEP`
+/// absorbed per poseidon hash block
 #[derive(Debug, Clone)]
-pub struct PoseidonCircuitConfig<F: Field>(pub(crate) PoseidonHashConfig<F>);
+pub struct PoseidonCircuitConfig<F: Field, const STEP: usize = HASH_BLOCK_STEP_SIZE>(
+    pub(crate) PoseidonHashConfig<F>,
+);
 
-const HASH_BLOCK_STEP_SIZE: usize = HASHBLOCK_BYTES_IN_FIELD * PoseidonTable::INPUT_WIDTH;
+/// Default number of bytes absorbed per poseidon hash block
+pub const HASH_BLOCK_STEP_SIZE: usize = HASHBLOCK_BYTES_IN_FIELD * PoseidonTable::INPUT_WIDTH;
 
-impl<F: Field> SubCircuitConfig<F> for PoseidonCircuitConfig<F> {
+impl<F: Field, const STEP: usize> SubCircuitConfig<F> for PoseidonCircuitConfig<F, STEP> {
     type ConfigArgs = PoseidonCircuitConfigArgs;
 
     fn new(
@@ -47,14 +84,72 @@ impl<F: Field> SubCircuitConfig<F> for PoseidonCircuitConfig<F> {
                 poseidon_table.heading_mark,
             ],
         );
-        let conf = PoseidonHashConfig::configure_sub(meta, poseidon_table, HASH_BLOCK_STEP_SIZE);
+        let conf = PoseidonHashConfig::configure_sub(meta, poseidon_table, STEP);
         Self(conf)
     }
 }
 
+/// Checks that the claimed hash of every `(inputs, domain, hash)` triple is
+/// the poseidon hash of its inputs in its domain, returning an error naming
+/// the first triple that doesn't match. Triples without a claimed hash are
+/// skipped.
+pub fn check_hash_triples<F: Field>(triples: &[([F; 2], F, Option<F>)]) -> Result<(), String> {
+    for (idx, (inputs, domain, hash)) in triples.iter().enumerate() {
+        if let Some(hash) = hash {
+            let expected = F::hash_with_domain(*inputs, *domain);
+            if *hash != expected {
+                return Err(format!(
+                    "poseidon triple {idx} with inputs {inputs:?} and domain {domain:?} claims hash {hash:?} instead of {expected:?}"
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+impl<F: Field, const STEP: usize, H> PoseidonCircuit<F, STEP, H> {
+    /// Build one circuit instance holding the hashes of all the `tables`, in
+    /// order, e.g. to compose several poseidon tables into one recursive
+    /// proof. Panics if the combined tables hold more than `max_hashes`.
+    pub fn from_tables(tables: Vec<PoseidonHashTable<F>>, max_hashes: usize) -> Self {
+        let mut poseidon_table_data: PoseidonHashTable<F> = PoseidonHashTable::default();
+        for mut table in tables {
+            poseidon_table_data.inputs.append(&mut table.inputs);
+            poseidon_table_data.controls.append(&mut table.controls);
+            poseidon_table_data.checks.append(&mut table.checks);
+        }
+        let num_hashes = poseidon_table_data.inputs.len();
+        assert!(
+            num_hashes <= max_hashes,
+            "poseidon max_hashes: {max_hashes} not enough. {num_hashes} needed by the tables"
+        );
+
+        Self(poseidon_table_data, max_hashes, PhantomData)
+    }
+}
+
 #[cfg(any(feature = "test", test))]
-impl<F: Field> SubCircuit<F> for PoseidonCircuit<F> {
-    type Config = PoseidonCircuitConfig<F>;
+impl<F: Field, const STEP: usize, H: CodeHasher<F>> PoseidonCircuit<F, STEP, H> {
+    /// Same as `new_from_block`, but with `integrity_check` the hash triples
+    /// of the zktrie proofs are checked by [`check_hash_triples`] first, so
+    /// that a wrong witness is reported with the offending triple rather than
+    /// failing obscurely in synthesis.
+    #[cfg_attr(not(feature = "zktrie"), allow(unused_variables))]
+    pub fn new_from_block_with_integrity_check(
+        block: &witness::Block<F>,
+        integrity_check: bool,
+    ) -> Result<Self, String> {
+        #[cfg(feature = "zktrie")]
+        if integrity_check {
+            check_hash_triples(&block.poseidon_triples())?;
+        }
+        Ok(Self::new_from_block(block))
+    }
+}
+
+#[cfg(any(feature = "test", test))]
+impl<F: Field, const STEP: usize, H: CodeHasher<F>> SubCircuit<F> for PoseidonCircuit<F, STEP, H> {
+    type Config = PoseidonCircuitConfig<F, STEP>;
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
         let max_hashes = block.circuits_params.max_poseidon_rows / F::hash_block_size();
@@ -63,7 +158,7 @@ impl<F: Field> SubCircuit<F> for PoseidonCircuit<F> {
         // without any feature we just synthesis an empty poseidon circuit
         #[cfg(feature = "zktrie")]
         {
-            let mpt_hashes = get_storage_poseidon_witness(block);
+            let mpt_hashes = block.poseidon_triples();
             if mpt_hashes.len() > max_hashes {
                 log::error!(
                     "poseidon max_hashes: {:?} not enough. {:?} needed by zktrie proof",
@@ -75,22 +170,20 @@ impl<F: Field> SubCircuit<F> for PoseidonCircuit<F> {
         }
         #[cfg(feature = "poseidon-codehash")]
         {
-            use crate::bytecode_circuit::bytecode_unroller::unroll_to_hash_input_default;
             for bytecode in block.bytecodes.values() {
                 // must skip empty bytecode
                 if !bytecode.bytes.is_empty() {
-                    let unrolled_inputs =
-                        unroll_to_hash_input_default::<F>(bytecode.bytes.iter().copied());
+                    let unrolled_inputs = H::hash_inputs(&bytecode.bytes);
                     poseidon_table_data.stream_inputs(
                         &unrolled_inputs,
                         bytecode.bytes.len() as u64,
-                        HASH_BLOCK_STEP_SIZE,
+                        STEP,
                     );
                 }
             }
         }
 
-        Self(poseidon_table_data, max_hashes)
+        Self(poseidon_table_data, max_hashes, PhantomData)
     }
 
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
@@ -162,13 +255,22 @@ impl<F: Field> SubCircuit<F> for PoseidonCircuit<F> {
             + storage_counter.len();
         log::debug!("poseidon circuit row num: dedup mpt from {prev_dedup_size} to {after_dedup_size}, mpt update len {}, smt trace len {}",
         block.mpt_updates.len(), block.mpt_updates.smt_traces.len());
-        let mpt_row_num = after_dedup_size * F::hash_block_size();
-        let byte_row_num = block
-            .bytecodes
-            .values()
-            .map(|bytecode| bytecode.bytes.len() / HASH_BLOCK_STEP_SIZE + 1)
-            .sum::<usize>()
-            * F::hash_block_size();
+        // only count the hashes that `new_from_block` loads for the enabled features
+        let mpt_row_num = if cfg!(feature = "zktrie") {
+            after_dedup_size * F::hash_block_size()
+        } else {
+            0
+        };
+        let byte_row_num = if cfg!(feature = "poseidon-codehash") {
+            block
+                .bytecodes
+                .values()
+                .map(|bytecode| bytecode.bytes.len() / STEP + 1)
+                .sum::<usize>()
+                * F::hash_block_size()
+        } else {
+            0
+        };
         let total_row_num = mpt_row_num + byte_row_num;
         log::debug!("poseidon circuit row num: {mpt_row_num}(mpt) + {byte_row_num}(bytecode) = {total_row_num}");
         (
@@ -185,11 +287,7 @@ impl<F: Field> SubCircuit<F> for PoseidonCircuit<F> {
         _challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
-        let chip = PoseidonHashChip::<_, HASH_BLOCK_STEP_SIZE>::construct(
-            config.0.clone(),
-            &self.0,
-            self.1,
-        );
+        let chip = PoseidonHashChip::<_, STEP>::construct(config.0.clone(), &self.0, self.1);
 
         chip.load(layouter)
     }
@@ -201,14 +299,16 @@ impl<F: Field> SubCircuit<F> for PoseidonCircuit<F> {
 }
 
 #[cfg(any(feature = "test", test))]
-impl<F: Field + Hashable> Circuit<F> for PoseidonCircuit<F> {
-    type Config = (PoseidonCircuitConfig<F>, Challenges);
+impl<F: Field + Hashable, const STEP: usize, H: CodeHasher<F>> Circuit<F>
+    for PoseidonCircuit<F, STEP, H>
+{
+    type Config = (PoseidonCircuitConfig<F, STEP>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
     #[cfg(feature = "circuit-params")]
     type Params = ();
 
     fn without_witnesses(&self) -> Self {
-        Self(Default::default(), self.1)
+        Self(Default::default(), self.1, PhantomData)
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -231,30 +331,257 @@ impl<F: Field + Hashable> Circuit<F> for PoseidonCircuit<F> {
     }
 }
 
-#[cfg(feature = "zktrie")]
-fn get_storage_poseidon_witness<F: Field>(
-    block: &crate::witness::Block<F>,
-) -> Vec<([F; 2], F, Option<F>)> {
-    use itertools::Itertools;
-    use mpt_zktrie::mpt_circuits::{gadgets::mpt_update::hash_traces, types::Proof};
-    hash_traces(
-        &block
-            .mpt_updates
-            .proof_types
-            .iter()
-            .cloned()
-            .zip_eq(block.mpt_updates.smt_traces.iter().cloned())
-            .map(Proof::from)
-            .collect_vec(),
-    )
-    .into_iter()
-    .unique_by(|(inp, domain, hash)| {
-        (
-            inp.map(|f| f.to_bytes()),
-            domain.to_bytes(),
-            hash.to_bytes(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::witness::block_convert;
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData};
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+    use mock::TestContext;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static HASHED_CODES: RefCell<Vec<Vec<u8>>> = RefCell::new(Vec::new());
+    }
+
+    /// Records the bytecodes it is asked to hash, and hashes them as the
+    /// default hasher does.
+    #[derive(Default, Clone, Debug)]
+    struct RecordingCodeHasher;
+
+    impl CodeHasher<Fr> for RecordingCodeHasher {
+        fn hash_inputs(code: &[u8]) -> Vec<[Fr; PoseidonTable::INPUT_WIDTH]> {
+            HASHED_CODES.with(|codes| codes.borrow_mut().push(code.to_vec()));
+            PoseidonCodeHasher::hash_inputs(code)
+        }
+    }
+
+    #[test]
+    fn poseidon_circuit_integrity_check() {
+        let inputs = [Fr::from(1), Fr::from(2)];
+        let domain = Fr::from(3);
+        assert_eq!(
+            check_hash_triples(&[
+                (inputs, domain, Some(Fr::hash_with_domain(inputs, domain))),
+                (inputs, domain, None),
+            ]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn poseidon_circuit_integrity_check_wrong_hash() {
+        let inputs = [Fr::from(1), Fr::from(2)];
+        let domain = Fr::from(3);
+        let hash = Fr::hash_with_domain(inputs, domain);
+        let err = check_hash_triples(&[
+            (inputs, domain, Some(hash)),
+            (inputs, domain, Some(hash + Fr::from(1))),
+        ])
+        .unwrap_err();
+        assert!(err.starts_with("poseidon triple 1 "), "{err}");
+    }
+
+    fn hash_table(seed: u64, num_hashes: u64) -> PoseidonHashTable<Fr> {
+        let triples = (0..num_hashes)
+            .map(|i| {
+                let inputs = [Fr::from(seed + i), Fr::from(seed * i)];
+                let domain = Fr::from(i);
+                (inputs, domain, Some(Fr::hash_with_domain(inputs, domain)))
+            })
+            .collect::<Vec<_>>();
+        let mut table = PoseidonHashTable::default();
+        table.fixed_inputs(&triples);
+        table
+    }
+
+    #[test]
+    fn poseidon_circuit_from_tables() {
+        let circuit =
+            PoseidonCircuit::<Fr>::from_tables(vec![hash_table(1, 3), hash_table(7, 2)], 8);
+        assert_eq!(circuit.0.inputs.len(), 5);
+
+        let k = 12;
+        let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "poseidon max_hashes: 4 not enough")]
+    fn poseidon_circuit_from_tables_overflow() {
+        PoseidonCircuit::<Fr>::from_tables(vec![hash_table(1, 3), hash_table(7, 2)], 4);
+    }
+
+    #[cfg(all(feature = "zktrie", feature = "scroll"))]
+    #[test]
+    fn poseidon_circuit_block_triples() {
+        use crate::witness::block_apply_mpt_state;
+        use bus_mapping::circuit_input_builder::{CircuitInputBuilder, CircuitsParams};
+
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        })
+        .unwrap();
+        let mut builder = CircuitInputBuilder::new_from_l2_trace(
+            CircuitsParams::default(),
+            ctx.l2_trace().clone(),
+            false,
+            false,
         )
-    })
-    .map(|(inp, domain, hash)| (inp.map(F::from), domain.into(), Some(F::from(hash))))
-    .collect()
+        .unwrap();
+        builder.finalize_building().unwrap();
+        let mut block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        block_apply_mpt_state(&mut block, &builder.mpt_init_state.unwrap());
+
+        let triples = block.poseidon_triples();
+        assert!(!triples.is_empty());
+        assert_eq!(check_hash_triples(&triples), Ok(()));
+        block.circuits_params.max_poseidon_rows = 1 << 10;
+        assert!(PoseidonCircuit::<Fr>::new_from_block_with_integrity_check(&block, true).is_ok());
+        // `min_num_rows_block` over-estimates the leaf hashes, so it must
+        // leave room for every triple.
+        let (num_rows, _) = PoseidonCircuit::<Fr>::min_num_rows_block(&block);
+        assert!(triples.len() * Fr::hash_block_size() <= num_rows);
+    }
+
+    /// Packs 16 bytes per field, so that a hash block absorbs 32 bytes.
+    #[cfg(feature = "poseidon-codehash")]
+    #[derive(Default, Clone, Debug)]
+    struct HalfBlockCodeHasher;
+
+    #[cfg(feature = "poseidon-codehash")]
+    impl CodeHasher<Fr> for HalfBlockCodeHasher {
+        fn hash_inputs(code: &[u8]) -> Vec<[Fr; PoseidonTable::INPUT_WIDTH]> {
+            crate::bytecode_circuit::bytecode_unroller::unroll_to_hash_input::<
+                Fr,
+                16,
+                { PoseidonTable::INPUT_WIDTH },
+            >(code.iter().copied())
+        }
+    }
+
+    #[cfg(feature = "poseidon-codehash")]
+    #[test]
+    fn poseidon_circuit_step_size() {
+        // 101 bytes of code take 2 hash blocks of the default 62 bytes and 4
+        // of 32 bytes, the other hashes of the block are the same for both.
+        let mut code = bytecode! {};
+        for _ in 0..50 {
+            code.push(1, 0x01);
+        }
+        code.op_stop();
+        let geth_data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let mut builder =
+            BlockData::new_from_geth_data(geth_data.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+            .unwrap();
+        let mut block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        block.circuits_params.max_poseidon_rows = 1 << 10;
+
+        let (default_rows, _) = PoseidonCircuit::<Fr>::min_num_rows_block(&block);
+        let (half_block_rows, _) =
+            PoseidonCircuit::<Fr, 32, HalfBlockCodeHasher>::min_num_rows_block(&block);
+        assert_eq!(half_block_rows - default_rows, 2 * Fr::hash_block_size());
+
+        let k = 12;
+        let default_circuit = PoseidonCircuit::<Fr>::new_from_block(&block);
+        let prover = MockProver::<Fr>::run(k, &default_circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        let half_block_circuit =
+            PoseidonCircuit::<Fr, 32, HalfBlockCodeHasher>::new_from_block(&block);
+        let prover = MockProver::<Fr>::run(k, &half_block_circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+        assert_eq!(
+            half_block_circuit.0.inputs.len() - default_circuit.0.inputs.len(),
+            2
+        );
+    }
+
+    #[test]
+    fn poseidon_circuit_code_hasher() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        };
+        let geth_data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code.clone())
+            .unwrap()
+            .into();
+        let mut builder =
+            BlockData::new_from_geth_data(geth_data.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+            .unwrap();
+        let mut block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        block.circuits_params.max_poseidon_rows = 1 << 10;
+
+        let circuit =
+            PoseidonCircuit::<Fr, HASH_BLOCK_STEP_SIZE, RecordingCodeHasher>::new_from_block(
+                &block,
+            );
+        let hashed_codes = HASHED_CODES.with(|codes| codes.take());
+        // bytecodes are only hashed with poseidon code hash
+        if cfg!(feature = "poseidon-codehash") {
+            assert!(hashed_codes.contains(&code.code()));
+            assert!(hashed_codes.iter().all(|code| !code.is_empty()));
+        } else {
+            assert!(hashed_codes.is_empty());
+        }
+
+        // the recorded hash inputs are the default ones, so the circuit still verifies
+        let k = 12;
+        let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn poseidon_circuit_rows_follow_features() {
+        let geth_data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        })
+        .unwrap()
+        .into();
+        let mut builder =
+            BlockData::new_from_geth_data(geth_data.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        let (rows, _) = PoseidonCircuit::<Fr>::min_num_rows_block(&block);
+
+        // bytecodes only take rows with poseidon code hash
+        let mut block_without_bytecodes = block.clone();
+        block_without_bytecodes.bytecodes.clear();
+        let (rows_without_bytecodes, _) =
+            PoseidonCircuit::<Fr>::min_num_rows_block(&block_without_bytecodes);
+        assert_eq!(
+            rows_without_bytecodes < rows,
+            cfg!(feature = "poseidon-codehash")
+        );
+
+        // zktrie proofs only take rows with zktrie
+        if !cfg!(feature = "zktrie") {
+            let mut block_without_traces = block.clone();
+            block_without_traces.mpt_updates.smt_traces.clear();
+            assert_eq!(
+                PoseidonCircuit::<Fr>::min_num_rows_block(&block_without_traces),
+                PoseidonCircuit::<Fr>::min_num_rows_block(&block),
+            );
+        }
+
+        #[cfg(not(any(feature = "zktrie", feature = "poseidon-codehash")))]
+        assert_eq!(rows, 0);
+    }
 }
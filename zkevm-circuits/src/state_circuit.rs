@@ -895,6 +895,24 @@ impl<F: Field> StateCircuit<F> {
     }
 }
 
+#[cfg(any(feature = "test", test))]
+impl<F: Field> StateCircuit<F> {
+    /// Returns the `(value, value_prev)` of every row, computed with the
+    /// `evm_word` challenge fixed to `challenge` instead of the one drawn by
+    /// the prover. The EVM circuit encodes the words it looks up in the rw
+    /// table with the same challenge, so under a fixed challenge both sides
+    /// are reproducible, which helps to track down value mismatches.
+    pub fn rw_value_rlcs(&self, challenge: F) -> Vec<(F, F)> {
+        self.rows
+            .iter()
+            .map(|rw| {
+                let row = rw.table_assignment_aux(challenge);
+                (row.value, row.value_prev)
+            })
+            .collect()
+    }
+}
+
 impl<F: Field> SubCircuit<F> for StateCircuit<F> {
     type Config = StateCircuitConfig<F>;
 
@@ -1,17 +1,20 @@
 #![allow(unused_imports)]
 pub use super::{dev::*, *};
 use crate::{
+    evm_circuit::util::rlc,
     table::{AccountFieldTag, CallContextFieldTag, RwTableTag, TxLogFieldTag, TxReceiptFieldTag},
     util::{unusable_rows, SubCircuit},
-    witness::{MptUpdates, Rw, RwMap},
+    witness::{block_convert, MptUpdates, Rw, RwMap},
 };
-use bus_mapping::operation::{
-    MemoryOp, Operation, OperationContainer, RWCounter, StackOp, StorageOp, RW,
+use bus_mapping::{
+    mock::BlockData,
+    operation::{MemoryOp, Operation, OperationContainer, RWCounter, StackOp, StorageOp, RW},
 };
 use eth_types::{
-    address,
+    address, bytecode,
     evm_types::{MemoryAddress, StackAddress},
-    Address, Field, ToAddress, Word, U256,
+    geth_types::GethData,
+    Address, Field, ToAddress, ToLittleEndian, Word, U256,
 };
 use gadgets::binary_number::AsBits;
 use halo2_proofs::{
@@ -22,6 +25,7 @@ use halo2_proofs::{
     plonk::{keygen_vk, Advice, Circuit, Column, ConstraintSystem},
     poly::kzg::commitment::ParamsKZG,
 };
+use mock::TestContext;
 use rand::SeedableRng;
 use std::collections::{BTreeSet, HashMap};
 use strum::IntoEnumIterator;
@@ -296,6 +300,40 @@ fn storage_key_rlc() {
     assert_eq!(verify(rows), Ok(()));
 }
 
+#[test]
+fn state_circuit_fixed_challenge_rlcs() {
+    let geth_data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! {
+        PUSH32(Word::MAX)
+        PUSH1(0x02)
+        ADD
+        STOP
+    })
+    .unwrap()
+    .into();
+    let mut builder = BlockData::new_from_geth_data(geth_data.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+        .unwrap();
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+    let circuit = StateCircuit::<Fr>::new(block.rws, N_ROWS);
+
+    let challenge = Fr::from(0x100);
+    let rlcs = circuit.rw_value_rlcs(challenge);
+    assert_eq!(rlcs, circuit.rw_value_rlcs(challenge));
+    assert_ne!(rlcs, circuit.rw_value_rlcs(Fr::from(0x101)));
+
+    // The stack values match the word RLCs the EVM circuit looks up under the
+    // same challenge.
+    let mut num_stack_rows = 0;
+    for (rw, (value, _)) in circuit.rows.iter().zip(rlcs) {
+        if let Rw::Stack { value: word, .. } = rw {
+            assert_eq!(value, rlc::value(&word.to_le_bytes(), challenge));
+            num_stack_rows += 1;
+        }
+    }
+    assert!(num_stack_rows > 0);
+}
+
 #[test]
 fn tx_log_ok() {
     let rows = vec![
@@ -96,7 +96,7 @@ use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, CircuitsParams},
     mock::BlockData,
 };
-use eth_types::{geth_types::GethData, Field};
+use eth_types::{evm_types::HardFork, geth_types::GethData, Field};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
     halo2curves::bn256::Fr,
@@ -104,6 +104,7 @@ use halo2_proofs::{
 };
 use itertools::Itertools;
 use snark_verifier_sdk::CircuitExt;
+use std::collections::BTreeMap;
 
 /// Configuration of the Super Circuit
 #[derive(Clone)]
@@ -149,6 +150,8 @@ pub struct SuperCircuitConfigArgs {
     pub mock_randomness: u64,
     /// Challenges
     pub challenges: crate::util::Challenges,
+    /// Hard fork whose gas refund rules are enforced by the EVM circuit.
+    pub hard_fork: HardFork,
 }
 
 impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
@@ -163,6 +166,7 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             max_inner_blocks: _,
             mock_randomness: _mock_randomness,
             challenges,
+            hard_fork,
         }: Self::ConfigArgs,
     ) -> Self {
         let log_circuit_info = |meta: &ConstraintSystem<Fr>, tag: &str| {
@@ -355,6 +359,7 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
                 modexp_table,
                 ecc_table,
                 pow_of_rand_table,
+                hard_fork,
             },
         );
         log_circuit_info(meta, "evm circuit");
@@ -414,6 +419,10 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
     }
 }
 
+/// Key of the entry returned by [`SuperCircuit::estimate_min_rows`] which
+/// holds the maximum row usage over all subcircuits.
+pub const AGGREGATE_ROWS_KEY: &str = "aggregate";
+
 /// Row usage for each sub circuit
 #[derive(Clone, Default, Debug)]
 pub struct SubcircuitRowUsage {
@@ -488,8 +497,42 @@ impl<
         // num_rows_evm_circuit.max(num_rows_tx_circuit)
         num_rows_evm_circuit
     }
+    /// Return the `(used, max)` rows of each subcircuit keyed by subcircuit
+    /// name, plus an [`AGGREGATE_ROWS_KEY`] entry holding the maximum over all
+    /// of them, which is what the Super Circuit needs to prove the block.
+    pub fn estimate_min_rows(block: &Block<Fr>) -> BTreeMap<&'static str, (usize, usize)> {
+        let rows = Self::subcircuits_rows(block);
+        let aggregate = (
+            itertools::max(rows.iter().map(|(_, (row_num_real, _))| *row_num_real)).unwrap(),
+            itertools::max(rows.iter().map(|(_, (_, row_num_total))| *row_num_total)).unwrap(),
+        );
+        rows.into_iter()
+            .chain(std::iter::once((AGGREGATE_ROWS_KEY, aggregate)))
+            .collect()
+    }
+
     /// Return the minimum number of rows required to prove the block
     pub fn min_num_rows_block_subcircuits(block: &Block<Fr>) -> Vec<SubcircuitRowUsage> {
+        let row_usage_details = Self::subcircuits_rows(block)
+            .into_iter()
+            .map(|(name, (row_num_real, row_num_total))| SubcircuitRowUsage {
+                name: name.to_string(),
+                row_num_real,
+                row_num_total,
+            })
+            .collect_vec();
+        {
+            let mut row_usage_details_sorted = row_usage_details.clone();
+            row_usage_details_sorted.sort_by_key(|r| r.row_num_real);
+            row_usage_details_sorted.reverse();
+            for detail in &row_usage_details_sorted {
+                log::debug!("row detail {} {}", detail.name, detail.row_num_real);
+            }
+        }
+        row_usage_details
+    }
+
+    fn subcircuits_rows(block: &Block<Fr>) -> Vec<(&'static str, (usize, usize))> {
         log::debug!("start min_num_rows_block_subcircuits");
         let mut rows = Vec::new();
         let mut push = |name, usage| {
@@ -518,7 +561,7 @@ impl<
         push("mod_exp", mod_exp);
         let pi = PiCircuit::min_num_rows_block(block);
         push("pi", pi);
-        let poseidon = PoseidonCircuit::min_num_rows_block(block);
+        let poseidon = PoseidonCircuit::<_>::min_num_rows_block(block);
         push("poseidon", poseidon);
         let sig = SigCircuit::min_num_rows_block(block);
         push("sig", sig);
@@ -529,24 +572,7 @@ impl<
             let mpt = MptCircuit::<Fr>::min_num_rows_block(block);
             push("mpt", mpt);
         }
-
-        let row_usage_details = rows
-            .into_iter()
-            .map(|(name, (row_num_real, row_num_total))| SubcircuitRowUsage {
-                name: name.to_string(),
-                row_num_real,
-                row_num_total,
-            })
-            .collect_vec();
-        {
-            let mut row_usage_details_sorted = row_usage_details.clone();
-            row_usage_details_sorted.sort_by_key(|r| r.row_num_real);
-            row_usage_details_sorted.reverse();
-            for detail in &row_usage_details_sorted {
-                log::debug!("row detail {} {}", detail.name, detail.row_num_real);
-            }
-        }
-        row_usage_details
+        rows
     }
 }
 
@@ -754,6 +780,7 @@ impl<
                     max_inner_blocks: MAX_INNER_BLOCKS,
                     mock_randomness: MOCK_RANDOMNESS,
                     challenges,
+                    hard_fork: HardFork::default(),
                 },
             ),
             challenges,
@@ -798,6 +825,14 @@ impl<
         const MOCK_RANDOMNESS: u64,
     > SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
 {
+    /// Returns the public input values of all the sub circuits, concatenated
+    /// in the order of their instance columns, for an aggregation layer to
+    /// commit to. The poseidon and mpt circuits have no instance columns, the
+    /// state roots they work on are committed to by the pi circuit instead.
+    pub fn instance_commitments(&self) -> Vec<Fr> {
+        self.instance().concat()
+    }
+
     /// From the witness data, generate a SuperCircuit instance with all of the
     /// sub-circuits filled with their corresponding witnesses.
     ///
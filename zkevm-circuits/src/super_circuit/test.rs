@@ -54,6 +54,68 @@ fn super_circuit_degree() {
     assert!(cs.degree() <= 9);
 }
 
+#[test]
+fn super_circuit_estimate_min_rows() {
+    let block = block_1tx();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    let rows = SuperCircuit::<Fr, 1, 256, 64, 0x100>::estimate_min_rows(&block);
+    let aggregate = rows[AGGREGATE_ROWS_KEY];
+    let subcircuits = rows
+        .iter()
+        .filter(|(name, _)| **name != AGGREGATE_ROWS_KEY)
+        .map(|(_, usage)| *usage)
+        .collect_vec();
+    assert!(subcircuits.len() > 1);
+    assert_eq!(
+        aggregate,
+        (
+            subcircuits.iter().map(|(real, _)| *real).max().unwrap(),
+            subcircuits.iter().map(|(_, total)| *total).max().unwrap(),
+        )
+    );
+    assert_eq!(rows["evm"], EvmCircuit::<Fr>::min_num_rows_block(&block));
+}
+
+#[test]
+fn super_circuit_instance_commitments() {
+    let block = block_1tx();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    let circuit = SuperCircuit::<Fr, 1, 256, 64, 0x100>::new_from_block(&block);
+    #[allow(unused_mut)]
+    let mut subcircuit_instances = vec![
+        circuit.keccak_circuit.instance(),
+        circuit.pi_circuit.instance(),
+        circuit.tx_circuit.instance(),
+        circuit.bytecode_circuit.instance(),
+        circuit.copy_circuit.instance(),
+        circuit.state_circuit.instance(),
+        circuit.exp_circuit.instance(),
+        circuit.evm_circuit.instance(),
+        circuit.poseidon_circuit.instance(),
+    ];
+    #[cfg(feature = "zktrie")]
+    subcircuit_instances.push(circuit.mpt_circuit.instance());
+    // Each subcircuit takes the next slice of the commitments, in order.
+    let commitments = circuit.instance_commitments();
+    let mut offset = 0;
+    for instance in subcircuit_instances {
+        let instance = instance.concat();
+        assert_eq!(commitments[offset..offset + instance.len()], instance[..]);
+        offset += instance.len();
+    }
+    assert_eq!(offset, commitments.len());
+}
+
 #[cfg(feature = "scroll")]
 fn test_super_circuit<
     const MAX_TXS: usize,
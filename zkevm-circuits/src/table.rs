@@ -99,6 +99,24 @@ pub trait LookupTable<F: Field> {
             .zip(self.annotations().iter())
             .for_each(|(&col, ann)| region.name_column(|| ann, col))
     }
+
+    /// Formats rows of values assigned to the table, given in the table column
+    /// order, with one line per row, so that the table content can be printed
+    /// when debugging a failing lookup.
+    fn format_rows(&self, rows: &[Vec<Value<F>>]) -> String {
+        let annotations = self.annotations();
+        rows.iter()
+            .enumerate()
+            .map(|(idx, row)| {
+                let cells = annotations
+                    .iter()
+                    .zip(row)
+                    .map(|(ann, value)| format!("{ann}: {value:?}"))
+                    .join(", ");
+                format!("row {idx}: {cells}")
+            })
+            .join("\n")
+    }
 }
 
 impl<F: Field, C: Into<Column<Any>> + Copy, const W: usize> LookupTable<F> for [C; W] {
@@ -992,6 +1010,17 @@ impl PoseidonTable {
         layouter: &mut impl Layouter<F>,
         inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
     ) -> Result<(), Error> {
+        self.dev_load_and_dump(layouter, inputs).map(|_| ())
+    }
+
+    /// Same as `dev_load`, but also returns the values of the assigned rows in
+    /// the table column order, to be inspected (see
+    /// [`LookupTable::format_rows`]) in tests.
+    pub fn dev_load_and_dump<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+    ) -> Result<Vec<Vec<Value<F>>>, Error> {
         use crate::bytecode_circuit::bytecode_unroller::{
             unroll_to_hash_input_default, HASHBLOCK_BYTES_IN_FIELD,
         };
@@ -1002,23 +1031,29 @@ impl PoseidonTable {
             || "poseidon codehash table",
             |mut region| {
                 let mut offset = 0;
+                let mut rows = vec![];
                 let poseidon_table_columns =
                     <PoseidonTable as LookupTable<F>>::advice_columns(self);
 
-                region.assign_fixed(
-                    || "poseidon table all-zero row",
-                    self.q_enable,
-                    offset,
-                    || Value::known(F::zero()),
-                )?;
+                let mut row = vec![region
+                    .assign_fixed(
+                        || "poseidon table all-zero row",
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::zero()),
+                    )?
+                    .value()
+                    .copied()];
                 for column in poseidon_table_columns.iter().copied() {
-                    region.assign_advice(
+                    let cell = region.assign_advice(
                         || "poseidon table all-zero row",
                         column,
                         offset,
                         || Value::known(F::zero()),
                     )?;
+                    row.push(cell.value().copied());
                 }
+                rows.push(row);
                 offset += 1;
                 // let nil_hash =
                 //     Value::known(CodeDB::empty_code_hash().to_word().to_scalar().unwrap());
@@ -1062,12 +1097,15 @@ impl PoseidonTable {
                         let control_len_as_flag =
                             F::from_u128(HASHABLE_DOMAIN_SPEC * control_len as u128);
 
-                        region.assign_fixed(
-                            || format!("poseidon table row {offset}"),
-                            self.q_enable,
-                            offset,
-                            || Value::known(F::one()),
-                        )?;
+                        let mut dumped_row = vec![region
+                            .assign_fixed(
+                                || format!("poseidon table row {offset}"),
+                                self.q_enable,
+                                offset,
+                                || Value::known(F::one()),
+                            )?
+                            .value()
+                            .copied()];
                         for (column, value) in poseidon_table_columns.iter().zip_eq(
                             once(ref_hash)
                                 .chain(row.map(Value::known))
@@ -1079,13 +1117,15 @@ impl PoseidonTable {
                                     F::zero()
                                 }))),
                         ) {
-                            region.assign_advice(
+                            let cell = region.assign_advice(
                                 || format!("poseidon table row {offset}"),
                                 *column,
                                 offset,
                                 || value,
                             )?;
+                            dumped_row.push(cell.value().copied());
                         }
+                        rows.push(dumped_row);
                         first_row = false;
                         offset += 1;
                         control_len = if control_len > block_size {
@@ -1101,7 +1141,7 @@ impl PoseidonTable {
                         input.len()
                     );
                 }
-                Ok(())
+                Ok(rows)
             },
         )
     }
@@ -3151,3 +3191,65 @@ impl<const MAX: usize> From<RangeTable<MAX>> for TableColumn {
         table.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::escape_value;
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Circuit, ConstraintSystem},
+    };
+
+    #[derive(Default)]
+    struct PoseidonTableDumpCircuit {
+        inputs: Vec<Vec<u8>>,
+    }
+
+    impl Circuit<Fr> for PoseidonTableDumpCircuit {
+        type Config = PoseidonTable;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            PoseidonTable::construct(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let rows = config.dev_load_and_dump(&mut layouter, &self.inputs)?;
+            log::debug!("poseidon table:\n{}", config.format_rows(&rows));
+
+            // all-zero row followed by one row per hash block of the input
+            assert_eq!(rows.len(), 2);
+            assert!(rows
+                .iter()
+                .all(|row| row.len() == LookupTable::<Fr>::columns(&config).len()));
+            if let Some(q_enable) = escape_value(rows[0][0]) {
+                assert_eq!(q_enable, Fr::zero());
+                assert_eq!(escape_value(rows[1][0]), Some(Fr::one()));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn poseidon_table_dump() {
+        let circuit = PoseidonTableDumpCircuit {
+            inputs: vec![vec![0x60, 0x01, 0x00]],
+        };
+        let prover = MockProver::<Fr>::run(6, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
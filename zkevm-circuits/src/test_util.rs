@@ -5,17 +5,20 @@ use crate::{
     evm_circuit::EvmCircuit,
     state_circuit::StateCircuit,
     util::{log2_ceil, SubCircuit},
-    witness::{Block, Rw},
+    witness::{Block, Rw, WitnessError},
 };
 use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
-use eth_types::geth_types::GethData;
+use eth_types::{
+    bytecode, evm_types::OpcodeId, geth_types::GethData, Address, Bytecode, ToWord, Word,
+};
 
 use halo2_proofs::{
     circuit::Value,
     dev::{unwrap_value, MockProver},
     halo2curves::bn256::Fr,
+    plonk::Error,
 };
-use mock::TestContext;
+use mock::{eth, TestContext, MOCK_ACCOUNTS};
 
 #[cfg(feature = "scroll")]
 use bus_mapping::circuit_input_builder::CircuitInputBuilder;
@@ -86,6 +89,8 @@ pub struct CircuitTestBuilder<const NACC: usize, const NTX: usize> {
     state_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
     copy_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
     block_modifiers: Vec<Box<dyn Fn(&mut Block<Fr>)>>,
+    minimal_fixed_table: bool,
+    validate_rw_counters: bool,
 }
 
 impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
@@ -114,6 +119,8 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
                 ), Ok(()));
             })),
             block_modifiers: vec![],
+            minimal_fixed_table: false,
+            validate_rw_counters: false,
         }
     }
 
@@ -196,13 +203,41 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         self.block_modifiers.push(modifier);
         self
     }
+
+    /// Only load the fixed table rows of the tags queried by the gadgets
+    /// executed in the block, instead of the full fixed table, when running
+    /// the EVM Circuit verification.
+    pub fn minimal_fixed_table(mut self) -> Self {
+        self.minimal_fixed_table = true;
+        self
+    }
+
+    /// Check the rw counters of the block with [`Block::validate_rw_counters`]
+    /// before running the circuits, to report broken counters of the witness
+    /// instead of a failure of the State Circuit.
+    pub fn validate_rw_counters(mut self) -> Self {
+        self.validate_rw_counters = true;
+        self
+    }
 }
 
 impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
     /// Triggers the `CircuitTestBuilder` to convert the [`TestContext`] if any,
     /// into a [`Block`] and apply the default or provided block_modifiers or
     /// circuit checks to the provers generated for the State and EVM circuits.
+    ///
+    /// Panics with the error of [`Self::try_run`] if the block doesn't fit
+    /// its circuits params.
     pub fn run(self) {
+        if let Err(err) = self.try_run() {
+            panic!("{err}");
+        }
+    }
+
+    /// Does the same as [`Self::run`], but returns the [`WitnessError`] of a
+    /// block that doesn't fit its circuits params instead of panicking. The
+    /// checks still panic when the provers fail.
+    pub fn try_run(self) -> Result<(), WitnessError> {
         let mut params = if let Some(block) = self.block.as_ref() {
             block.circuits_params
         } else {
@@ -259,6 +294,14 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             panic!("No attribute to build a block was passed to the CircuitTestBuilder")
         };
 
+        block.check_rw_capacity()?;
+
+        if self.validate_rw_counters {
+            if let Err(err) = block.validate_rw_counters() {
+                panic!("invalid rw counters: {err}");
+            }
+        }
+
         const NUM_BLINDING_ROWS: usize = 64;
         // Run evm circuit test
         if let Some(evm_checks) = &self.evm_checks {
@@ -266,7 +309,11 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             assert!(k <= 20);
             let (active_gate_rows, active_lookup_rows) = EvmCircuit::<Fr>::get_active_rows(&block);
 
-            let circuit = EvmCircuit::get_test_cicuit_from_block(block.clone());
+            let circuit = if self.minimal_fixed_table {
+                EvmCircuit::new_dev_minimal_fixed_table(block.clone())
+            } else {
+                EvmCircuit::get_test_cicuit_from_block(block.clone())
+            };
             let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
 
             evm_checks(prover, &active_gate_rows, &active_lookup_rows)
@@ -304,6 +351,206 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
 
             copy_checks(prover, &rows, &rows);
         }
+
+        Ok(())
+    }
+}
+
+/// Runs the EVM circuit on the block built from `ctx` after `tamper` has been
+/// applied to it, and asserts that the constraints are no longer satisfied.
+/// This is meant to catch under-constrained gadgets, so `tamper` should only
+/// corrupt the witness of the gadget under test.
+pub fn assert_gadget_rejects<const NACC: usize, const NTX: usize>(
+    ctx: TestContext<NACC, NTX>,
+    tamper: Box<dyn Fn(&mut Block<Fr>)>,
+) {
+    CircuitTestBuilder::new_from_test_ctx(ctx)
+        .block_modifier(tamper)
+        .evm_checks(Some(Box::new(|prover, gate_rows, lookup_rows| {
+            assert!(prover
+                .verify_at_rows_par(gate_rows.iter().cloned(), lookup_rows.iter().cloned())
+                .is_err())
+        })))
+        .state_checks(None)
+        .copy_checks(None)
+        .run();
+}
+
+/// Asserts that every bytecode of the block is keyed by, and carries, the
+/// code hash of its bytes: the poseidon hash under `poseidon-codehash` and the
+/// keccak hash otherwise. A mismatch makes the bytecode lookups of the EVM
+/// circuit fail on an otherwise valid trace.
+pub fn assert_code_hashes_consistent(block: &Block<Fr>) {
+    for (code_hash, bytecode) in &block.bytecodes {
+        #[cfg(feature = "poseidon-codehash")]
+        let expected = bus_mapping::util::hash_code_poseidon(&bytecode.bytes);
+        #[cfg(not(feature = "poseidon-codehash"))]
+        let expected = bus_mapping::util::hash_code_keccak(&bytecode.bytes);
+        let expected = Word::from_big_endian(expected.as_bytes());
+        assert_eq!(
+            *code_hash, bytecode.hash,
+            "bytecode keyed by {code_hash:#x} carries hash {:#x}",
+            bytecode.hash
+        );
+        assert_eq!(
+            bytecode.hash, expected,
+            "bytecode {:?} has hash {:#x}, expected {expected:#x}",
+            bytecode.bytes, bytecode.hash
+        );
+    }
+}
+
+/// Returns a [`TestContext`] whose tx calls the first of a chain of `depth + 1`
+/// contracts, each one CALLing the next with all its gas, and the last one
+/// running `inner`. The sender is the last account, and the accounts left in
+/// between are empty. This makes it easy to test the restore context of error
+/// gadgets at any call depth.
+pub fn nested_call_ctx<const NACC: usize>(depth: usize, inner: Bytecode) -> TestContext<NACC, 1> {
+    assert!(
+        depth + 2 <= NACC,
+        "{NACC} accounts can't hold {depth} nested calls and the sender"
+    );
+    let address = |idx: usize| Address::from_low_u64_be(0x1000 + idx as u64);
+
+    TestContext::new(
+        None,
+        |accs| {
+            for (idx, acc) in accs.into_iter().enumerate() {
+                acc.address(address(idx));
+                if idx < depth {
+                    acc.code(bytecode! {
+                        PUSH1(0) // retLength
+                        PUSH1(0) // retOffset
+                        PUSH1(0) // argsLength
+                        PUSH1(0) // argsOffset
+                        PUSH1(0) // value
+                        PUSH20(address(idx + 1).to_word()) // addr
+                        GAS
+                        CALL
+                        STOP
+                    });
+                } else if idx == depth {
+                    acc.code(inner.clone());
+                } else if idx == NACC - 1 {
+                    acc.balance(eth(10));
+                }
+            }
+        },
+        |mut txs, accs| {
+            txs[0].from(accs[NACC - 1].address).to(accs[0].address);
+        },
+        |block, _tx| block,
+    )
+    .unwrap()
+}
+
+/// Returns a [`TestContext`] whose tx, sending `tx_value`, calls the contract
+/// at `MOCK_ACCOUNTS[0]`, which runs `opcode` on the contract at
+/// `MOCK_ACCOUNTS[1]` and stops. `opcode` is either CALL, sending
+/// `call_value`, or DELEGATECALL, and the callee runs `callee_code`. The
+/// sender is `MOCK_ACCOUNTS[2]`. This makes it easy to compare what a gadget
+/// sees in a CALL and a DELEGATECALL frame.
+pub fn subcall_ctx(
+    opcode: OpcodeId,
+    tx_value: Word,
+    call_value: Word,
+    callee_code: Bytecode,
+) -> TestContext<3, 1> {
+    let mut caller_code = bytecode! {
+        PUSH1(0) // retLength
+        PUSH1(0) // retOffset
+        PUSH1(0) // argsLength
+        PUSH1(0) // argsOffset
+    };
+    match opcode {
+        OpcodeId::CALL => {
+            caller_code.push(32, call_value); // value
+        }
+        OpcodeId::DELEGATECALL => {}
+        _ => panic!("{opcode:?} is neither CALL nor DELEGATECALL"),
+    }
+    caller_code.append(&bytecode! {
+        PUSH20(MOCK_ACCOUNTS[1].to_word()) // addr
+        PUSH2(0xffff) // gas
+    });
+    caller_code.write_op(opcode);
+    caller_code.write_op(OpcodeId::STOP);
+
+    TestContext::new(
+        None,
+        |accs| {
+            accs[0].address(MOCK_ACCOUNTS[0]).code(caller_code);
+            accs[1].address(MOCK_ACCOUNTS[1]).code(callee_code);
+            accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(1));
+        },
+        |mut txs, accs| {
+            txs[0]
+                .from(accs[2].address)
+                .to(accs[0].address)
+                .value(tx_value);
+        },
+        |block, _tx| block,
+    )
+    .unwrap()
+}
+
+/// Error returned by [`run_with_budget`].
+#[derive(Debug)]
+pub enum BudgetError {
+    /// The execution steps of the block don't fit in the budget.
+    ExceedsBudget {
+        /// Least `max_evm_rows` the steps of the block fit in.
+        rows_needed: usize,
+        /// Rows the EVM circuit was sized to.
+        max_rows: usize,
+    },
+    /// The `MockProver` failed to synthesize the circuit.
+    Synthesis(Error),
+}
+
+impl std::fmt::Display for BudgetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ExceedsBudget {
+                rows_needed,
+                max_rows,
+            } => write!(f, "block needs {rows_needed} rows, budget is {max_rows}"),
+            Self::Synthesis(err) => write!(f, "synthesis failed: {err:?}"),
+        }
+    }
+}
+
+/// Runs the EVM circuit on `block` through a `MockProver`, with the execution
+/// steps padded to exactly `max_rows` rows (`max_evm_rows`). The `MockProver`
+/// is large enough for the tables of the block, so it's the step assignment
+/// that decides whether the block fits: running out of rows is returned as
+/// [`BudgetError::ExceedsBudget`] instead of a panic, so that callers can
+/// probe for the minimal budget of a block.
+pub fn run_with_budget(
+    mut block: Block<Fr>,
+    max_rows: usize,
+) -> Result<MockProver<Fr>, BudgetError> {
+    // The last EndBlock, its unused next row and at least one padding EndBlock.
+    let rows_needed = EvmCircuit::<Fr>::get_min_num_rows_required(&block) + 1;
+    let exceeds_budget = BudgetError::ExceedsBudget {
+        rows_needed,
+        max_rows,
+    };
+    // `max_evm_rows == 0` means dynamic height, which would ignore the budget.
+    if max_rows == 0 {
+        return Err(exceeds_budget);
+    }
+
+    block.circuits_params.max_evm_rows = max_rows;
+    let k = log2_ceil(block.get_evm_test_circuit_rows() + EvmCircuit::<Fr>::unusable_rows());
+    let circuit = EvmCircuit::get_test_cicuit_from_block(block);
+    match MockProver::<Fr>::run(k, &circuit, vec![]) {
+        Ok(prover) => Ok(prover),
+        Err(Error::NotEnoughRowsAvailable { .. }) => Err(exceeds_budget),
+        // The step assignment fails with `Error::Synthesis` when the steps
+        // don't fit in `max_evm_rows`.
+        Err(Error::Synthesis) if max_rows < rows_needed => Err(exceeds_budget),
+        Err(err) => Err(BudgetError::Synthesis(err)),
     }
 }
 
@@ -5,7 +5,7 @@ use std::collections::{BTreeMap, HashMap};
 use crate::evm_circuit::{detect_fixed_table_tags, EvmCircuit};
 
 use crate::{
-    evm_circuit::util::rlc,
+    evm_circuit::{param::STACK_CAPACITY, util::rlc},
     table::{BlockContextFieldTag, RwTableTag},
     util::SubCircuit,
 };
@@ -85,7 +85,51 @@ pub struct BlockContexts {
     pub relax_mode: bool,
 }
 
+/// A witness [`Block`] that doesn't fit the circuits it's assigned to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WitnessError {
+    /// The block has more rw entries, including the leading `Start` row, than
+    /// the `max_rws` rows of the rw table.
+    RwOverflow {
+        /// Rows the rw entries of the block take.
+        needed: usize,
+        /// `max_rws` of the circuits params.
+        max: usize,
+    },
+}
+
+impl std::fmt::Display for WitnessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RwOverflow { needed, max } => {
+                write!(f, "block needs {needed} rw rows but max_rws is {max}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WitnessError {}
+
 impl<F: Field> Block<F> {
+    /// Checks that the rw entries of the block fit in the `max_rws` rows of
+    /// the rw table. Otherwise the EVM circuit only fails on the padding
+    /// lookup of the EndBlock step.
+    pub fn check_rw_capacity(&self) -> Result<(), WitnessError> {
+        let needed = self
+            .rws
+            .0
+            .iter()
+            .filter(|(tag, _)| !matches!(tag, RwTableTag::Start))
+            .map(|(_, rws)| rws.len())
+            .sum::<usize>()
+            + 1;
+        let max = self.circuits_params.max_rws;
+        if needed > max {
+            return Err(WitnessError::RwOverflow { needed, max });
+        }
+        Ok(())
+    }
+
     /// For each tx, for each step, print the rwc at the beginning of the step,
     /// and all the rw operations of the step.
     pub(crate) fn debug_print_txs_steps_rw_ops(&self) {
@@ -143,6 +187,48 @@ impl<F: Field> Block<F> {
         self.precompile_events.get_sha256_events()
     }
 
+    /// Get the `(inputs, domain, hash)` poseidon triples of the zktrie
+    /// proofs of all the mpt updates in this block, deduplicated, as loaded
+    /// into the poseidon circuit.
+    #[cfg(feature = "zktrie")]
+    pub fn poseidon_triples(&self) -> Vec<([F; 2], F, Option<F>)> {
+        use mpt_zktrie::mpt_circuits::{gadgets::mpt_update::hash_traces, types::Proof};
+        hash_traces(
+            &self
+                .mpt_updates
+                .proof_types
+                .iter()
+                .cloned()
+                .zip_eq(self.mpt_updates.smt_traces.iter().cloned())
+                .map(Proof::from)
+                .collect_vec(),
+        )
+        .into_iter()
+        .unique_by(|(inp, domain, hash)| {
+            (
+                inp.map(|f| f.to_bytes()),
+                domain.to_bytes(),
+                hash.to_bytes(),
+            )
+        })
+        .map(|(inp, domain, hash)| (inp.map(F::from), domain.into(), Some(F::from(hash))))
+        .collect()
+    }
+
+    /// Get the distinct `(address, slot)` pairs of the storage read or written
+    /// in this block, in ascending order.
+    pub fn touched_storage_slots(&self) -> Vec<(Address, U256)> {
+        self.rws
+            .0
+            .get(&RwTableTag::AccountStorage)
+            .into_iter()
+            .flatten()
+            .filter_map(|rw| rw.address().zip(rw.storage_key()))
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
     pub(crate) fn print_evm_circuit_row_usage(&self) {
         let mut num_rows = 0;
         let mut counter = HashMap::new();
@@ -239,18 +325,449 @@ impl<F: Field> Block<F> {
     }
 }
 
+#[cfg(feature = "test")]
+use super::Rw;
 #[cfg(feature = "test")]
 use crate::exp_circuit::param::OFFSET_INCREMENT;
 use crate::tx_circuit::TX_LEN;
 #[cfg(feature = "test")]
 use crate::util::log2_ceil;
+#[cfg(feature = "test")]
+use bus_mapping::circuit_input_builder::{CopyBytes, CopyDataType, NumberOrHash};
+#[cfg(feature = "test")]
+use eth_types::{evm_types::OpcodeId, ToBigEndian, H256};
+#[cfg(feature = "test")]
+use serde::Deserialize;
+
+/// The fields of a JSON-RPC block header that are in the block table, along
+/// with the chain id which is not part of the header.
+#[cfg(feature = "test")]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonBlockHeader {
+    miner: Address,
+    gas_limit: U256,
+    number: U256,
+    timestamp: U256,
+    #[serde(default)]
+    difficulty: U256,
+    #[serde(default)]
+    mix_hash: H256,
+    #[serde(default)]
+    base_fee_per_gas: U256,
+    chain_id: U256,
+}
 
 #[cfg(feature = "test")]
 impl<F: Field> Block<F> {
+    /// Returns every byte string the block hashes with keccak: the tx RLPs,
+    /// ecrecover, public input, SHA3 and CREATE/CREATE2 preimages collected
+    /// when converting the block, plus the bytecodes when the code hash is
+    /// keccak. Each input appears once.
+    pub fn all_keccak_preimages(&self) -> Vec<Vec<u8>> {
+        #[cfg(not(feature = "poseidon-codehash"))]
+        let bytecodes = self
+            .bytecodes
+            .values()
+            .map(|bytecode| bytecode.bytes.clone())
+            .collect_vec();
+        #[cfg(feature = "poseidon-codehash")]
+        let bytecodes = vec![];
+
+        self.keccak_inputs
+            .iter()
+            .cloned()
+            .chain(bytecodes)
+            .unique()
+            .collect()
+    }
+
+    /// Checks that the rw counters are strictly increasing within every tag,
+    /// and that the counters of all the tags together are exactly `1..=N`.
+    /// Broken counters otherwise only show up as state circuit failures which
+    /// are hard to trace back to the witness.
+    pub fn validate_rw_counters(&self) -> Result<(), String> {
+        let tagged_rws = self
+            .rws
+            .0
+            .iter()
+            .filter(|(tag, _)| !matches!(tag, RwTableTag::Start))
+            .sorted_by_key(|(tag, _)| **tag as u64)
+            .collect_vec();
+
+        for (tag, rws) in tagged_rws.iter() {
+            for ((_, prev), (idx, rw)) in rws.iter().enumerate().tuple_windows() {
+                if rw.rw_counter() <= prev.rw_counter() {
+                    return Err(format!(
+                        "{tag:?} rw counter {} at index {idx} is not greater than the previous one {}",
+                        rw.rw_counter(),
+                        prev.rw_counter()
+                    ));
+                }
+            }
+        }
+
+        for (idx, rw_counter) in tagged_rws
+            .iter()
+            .flat_map(|(_, rws)| rws.iter().map(|rw| rw.rw_counter()))
+            .sorted()
+            .enumerate()
+        {
+            if rw_counter <= idx {
+                return Err(format!("rw counter {rw_counter} is duplicated"));
+            }
+            if rw_counter > idx + 1 {
+                return Err(format!("rw counter {} is missing", idx + 1));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the stack pointer of every step is within the stack, and
+    /// within the range the opcode of the step executes from without a stack
+    /// underflow or overflow, except for the steps that halt in an exception.
+    /// A wrong stack pointer otherwise only shows up as failing stack lookups
+    /// of the EVM circuit.
+    pub fn validate_stack_depths(&self) -> Result<(), String> {
+        for (tx_idx, tx) in self.txs.iter().enumerate() {
+            for (step_idx, step) in tx.steps.iter().enumerate() {
+                if step.stack_pointer > STACK_CAPACITY {
+                    return Err(format!(
+                        "tx {tx_idx} step {step_idx} has stack pointer {} above {STACK_CAPACITY}",
+                        step.stack_pointer
+                    ));
+                }
+                if step.execution_state.halts_in_exception() {
+                    continue;
+                }
+                if let Some(opcode) = step.opcode {
+                    let (min_stack_ptr, max_stack_ptr) = opcode.valid_stack_ptr_range();
+                    if step.stack_pointer < min_stack_ptr as usize {
+                        return Err(format!(
+                            "tx {tx_idx} step {step_idx} overflows the stack with {opcode:?} at stack pointer {}",
+                            step.stack_pointer
+                        ));
+                    }
+                    if step.stack_pointer > max_stack_ptr as usize {
+                        return Err(format!(
+                            "tx {tx_idx} step {step_idx} underflows the stack with {opcode:?} at stack pointer {}",
+                            step.stack_pointer
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the bytes the root call of the tx at `tx_index` reverted with, or
+    /// `None` if it didn't end with REVERT. The bytes are rebuilt from the
+    /// memory writes of the call before the REVERT step, so tests can assert
+    /// on revert reasons.
+    pub fn revert_reason(&self, tx_index: usize) -> Option<Vec<u8>> {
+        let tx = &self.txs[tx_index];
+        let step = tx.steps.iter().rev().find(|step| {
+            step.opcode == Some(OpcodeId::REVERT) && tx.calls[step.call_index].is_root
+        })?;
+        let call_id = tx.calls[step.call_index].id;
+        let offset = self.rws[step.rw_indices[0]].stack_value().as_usize();
+        let length = self.rws[step.rw_indices[1]].stack_value().as_usize();
+        if length == 0 {
+            return Some(vec![]);
+        }
+
+        let mut memory = vec![0u8; (offset + length).div_ceil(32) * 32];
+        for rw in self.rws.0.get(&RwTableTag::Memory).into_iter().flatten() {
+            if let Rw::Memory {
+                rw_counter,
+                is_write: true,
+                call_id: rw_call_id,
+                memory_address,
+                value,
+                ..
+            } = rw
+            {
+                let address = *memory_address as usize;
+                if *rw_call_id == call_id
+                    && *rw_counter < step.rw_counter
+                    && address + 32 <= memory.len()
+                {
+                    memory[address..address + 32].copy_from_slice(&value.to_be_bytes());
+                }
+            }
+        }
+        Some(memory[offset..offset + length].to_vec())
+    }
+
+    /// Writes every rw of the block, in rw counter order, to the CSV file at
+    /// `path` with the columns `rw_counter, target, is_write, address, key,
+    /// value, value_prev`. Columns a rw doesn't have are left empty.
+    #[cfg(feature = "debug-export")]
+    pub fn rws_to_csv(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(
+            file,
+            "rw_counter,target,is_write,address,key,value,value_prev"
+        )?;
+        for rw in self
+            .rws
+            .table_assignments_unsorted()
+            .into_iter()
+            .sorted_by_key(|rw| rw.rw_counter())
+        {
+            writeln!(
+                file,
+                "{},{:?},{},{},{},{:#x},{}",
+                rw.rw_counter(),
+                rw.tag(),
+                rw.is_write(),
+                rw.address()
+                    .map(|address| format!("{address:#x}"))
+                    .unwrap_or_default(),
+                rw.storage_key()
+                    .map(|key| format!("{key:#x}"))
+                    .unwrap_or_default(),
+                rw.value_word(),
+                rw.value_prev_word()
+                    .map(|value_prev| format!("{value_prev:#x}"))
+                    .unwrap_or_default(),
+            )?;
+        }
+        file.flush()
+    }
+
+    /// Asserts that `self` and `other` have the same rws, tag by tag and in
+    /// the same order. Panics at the first divergence with its tag, index and
+    /// the first differing column of the rw table, which helps to confirm
+    /// that a witness generation change preserves the rw trace.
+    pub fn assert_rw_eq(&self, other: &Self) {
+        const COLUMNS: [&str; 11] = [
+            "rw_counter",
+            "is_write",
+            "tag",
+            "id",
+            "address",
+            "field_tag",
+            "storage_key",
+            "value",
+            "value_prev",
+            "aux1",
+            "aux2",
+        ];
+        // Any randomness will do to tell apart the differing column.
+        let randomness = F::from(0x100);
+
+        let tags = self
+            .rws
+            .0
+            .keys()
+            .chain(other.rws.0.keys())
+            .copied()
+            .unique()
+            .sorted_by_key(|tag| *tag as u64);
+        for tag in tags {
+            let lhs = self.rws.0.get(&tag).map(Vec::as_slice).unwrap_or_default();
+            let rhs = other.rws.0.get(&tag).map(Vec::as_slice).unwrap_or_default();
+            for (idx, (lhs_rw, rhs_rw)) in lhs.iter().zip(rhs).enumerate() {
+                if lhs_rw == rhs_rw {
+                    continue;
+                }
+                let column = COLUMNS
+                    .iter()
+                    .zip_eq(
+                        lhs_rw
+                            .table_assignment_aux(randomness)
+                            .values()
+                            .into_iter()
+                            .zip_eq(rhs_rw.table_assignment_aux(randomness).values()),
+                    )
+                    .find(|(_, (lhs, rhs))| lhs != rhs)
+                    .map_or("value", |(column, _)| *column);
+                panic!("{tag:?} rw at index {idx} differs in {column}: {lhs_rw:?} != {rhs_rw:?}");
+            }
+            assert_eq!(
+                lhs.len(),
+                rhs.len(),
+                "{tag:?} rws differ in length at index {}",
+                lhs.len().min(rhs.len())
+            );
+        }
+    }
+
+    /// Appends a copy event moving `bytes` from `src_addr` to `dst_addr` in
+    /// the memory of call `call_id`, so that copy consuming gadgets can be
+    /// tested without a full trace. The memory word writes that put `bytes`
+    /// at `src_addr` come first, followed by the reads and writes the copy
+    /// looks up, so that the memory rws stay consistent. Both addresses must
+    /// be word aligned, `bytes` must be whole words and the two ranges must
+    /// not overlap.
+    pub fn add_memory_copy_event(
+        &mut self,
+        call_id: usize,
+        src_addr: u64,
+        dst_addr: u64,
+        bytes: &[u8],
+    ) {
+        let length = bytes.len() as u64;
+        assert!(
+            length > 0 && length % 32 == 0,
+            "copy length {length} is not a positive multiple of 32"
+        );
+        assert!(
+            src_addr % 32 == 0 && dst_addr % 32 == 0,
+            "copy addresses {src_addr:#x} and {dst_addr:#x} are not word aligned"
+        );
+        assert!(
+            src_addr + length <= dst_addr || dst_addr + length <= src_addr,
+            "copy ranges at {src_addr:#x} and {dst_addr:#x} overlap"
+        );
+
+        let mut rw_counter = self
+            .rws
+            .0
+            .values()
+            .flatten()
+            .map(|rw| rw.rw_counter())
+            .max()
+            .unwrap_or_default()
+            + 1;
+        let memory_rws = self.rws.0.entry(RwTableTag::Memory).or_default();
+        // The value the call last left at the word, zero if it never wrote it.
+        let last_value = |memory_rws: &[Rw], address: u64| {
+            memory_rws
+                .iter()
+                .rev()
+                .find_map(|rw| match rw {
+                    Rw::Memory {
+                        call_id: id,
+                        memory_address,
+                        value,
+                        ..
+                    } if *id == call_id && *memory_address == address => Some(*value),
+                    _ => None,
+                })
+                .unwrap_or_default()
+        };
+
+        for (offset, chunk) in (0..length).step_by(32).zip(bytes.chunks(32)) {
+            let value_prev = last_value(memory_rws, src_addr + offset);
+            memory_rws.push(Rw::Memory {
+                rw_counter,
+                is_write: true,
+                call_id,
+                memory_address: src_addr + offset,
+                value: Word::from_big_endian(chunk),
+                value_prev,
+            });
+            rw_counter += 1;
+        }
+
+        let rw_counter_start = rw_counter;
+        let mut bytes_write_prev = Vec::with_capacity(bytes.len());
+        for (offset, chunk) in (0..length).step_by(32).zip(bytes.chunks(32)) {
+            let value = Word::from_big_endian(chunk);
+            let value_prev = last_value(memory_rws, dst_addr + offset);
+            bytes_write_prev.extend(value_prev.to_be_bytes());
+            memory_rws.push(Rw::Memory {
+                rw_counter,
+                is_write: false,
+                call_id,
+                memory_address: src_addr + offset,
+                value,
+                value_prev: value,
+            });
+            memory_rws.push(Rw::Memory {
+                rw_counter: rw_counter + 1,
+                is_write: true,
+                call_id,
+                memory_address: dst_addr + offset,
+                value,
+                value_prev,
+            });
+            rw_counter += 2;
+        }
+
+        let steps = bytes.iter().map(|byte| (*byte, false, false)).collect_vec();
+        let copy_event = CopyEvent {
+            src_addr,
+            src_addr_end: src_addr + length,
+            src_type: CopyDataType::Memory,
+            src_id: NumberOrHash::Number(call_id),
+            dst_addr,
+            dst_type: CopyDataType::Memory,
+            dst_id: NumberOrHash::Number(call_id),
+            log_id: None,
+            rw_counter_start: rw_counter_start.into(),
+            copy_bytes: CopyBytes::new(steps.clone(), Some(steps), Some(bytes_write_prev)),
+            access_list: vec![],
+        };
+        assert_eq!(copy_event.full_length(), length);
+        assert_eq!(
+            copy_event.rw_counter_start() + copy_event.rw_counter_delta(),
+            rw_counter as u64,
+            "copy event rw counters are not linked to the memory rws"
+        );
+        self.copy_events.push(copy_event);
+    }
+
+    /// Overwrites the context of a single block witness with the coinbase, gas
+    /// limit, number, timestamp, prevrandao, base fee and chain id of a
+    /// JSON-RPC block header extended with a `chainId` field, so that block
+    /// context opcodes can be tested against realistic values. The steps and
+    /// txs are moved to the new block number, but values the trace already
+    /// depends on, like the coinbase credited in EndTx, are left untouched.
+    pub fn set_context_from_json_header(&mut self, header: &str) -> Result<(), serde_json::Error> {
+        let header: JsonBlockHeader = serde_json::from_str(header)?;
+        assert_eq!(
+            self.context.ctxs.len(),
+            1,
+            "only the context of a single block witness can be overwritten"
+        );
+        let (_, mut ctx) = self.context.ctxs.pop_first().unwrap();
+
+        let (number, chain_id) = (header.number.as_u64(), header.chain_id.as_u64());
+        ctx.coinbase = header.miner;
+        ctx.gas_limit = header.gas_limit.as_u64();
+        ctx.number = header.number;
+        ctx.timestamp = header.timestamp;
+        // The prevrandao takes the place of the difficulty after the merge.
+        ctx.difficulty = if header.difficulty.is_zero() {
+            header.mix_hash.to_fixed_bytes().into()
+        } else {
+            header.difficulty
+        };
+        ctx.base_fee = header.base_fee_per_gas;
+        ctx.chain_id = chain_id;
+        self.context.ctxs.insert(number, ctx);
+        self.chain_id = chain_id;
+
+        for tx in self.txs.iter_mut() {
+            tx.block_number = number;
+            tx.steps.iter_mut().for_each(|step| step.block_num = number);
+        }
+        self.end_block_not_last.block_num = number;
+        self.end_block_last.block_num = number;
+
+        Ok(())
+    }
+
     /// Obtains the expected Circuit degree needed in order to be able to test
     /// the EvmCircuit with this block without needing to configure the
     /// `ConstraintSystem`.
     pub fn get_evm_test_circuit_degree(&self) -> u32 {
+        let rows = self.get_evm_test_circuit_rows();
+        let k = log2_ceil(rows);
+        log::debug!("circuit uses k = {}, rows = {}", k, rows);
+        k
+    }
+
+    /// Obtains the number of rows, unusable rows included, needed in order to
+    /// test the EvmCircuit with this block.
+    pub fn get_evm_test_circuit_rows(&self) -> usize {
         let num_rows_required_for_execution_steps: usize =
             EvmCircuit::<F>::get_num_rows_required(self);
         let num_rows_required_for_rw_table: usize = self.circuits_params.max_rws;
@@ -290,7 +807,6 @@ impl<F: Field> Block<F> {
         ])
         .unwrap();
 
-        let k = log2_ceil(EvmCircuit::<F>::unusable_rows() + rows_needed);
         log::debug!(
             "num_rows_required_for rw_table={}, fixed_table={}, bytecode_table={}, \
             copy_table={}, keccak_table={}, tx_table={}, exp_table={}",
@@ -302,8 +818,7 @@ impl<F: Field> Block<F> {
             num_rows_required_for_tx_table,
             num_rows_required_for_exp_table
         );
-        log::debug!("circuit uses k = {}, rows = {}", k, rows_needed);
-        k
+        EvmCircuit::<F>::unusable_rows() + rows_needed
     }
 }
 
@@ -605,3 +1120,315 @@ pub fn block_mocking_apply_mpt<F: Field>(block: &mut Block<F>) {
     block.state_root = Some(block.mpt_updates.new_root());
     block.prev_state_root = block.mpt_updates.old_root();
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        evm_circuit::step::ExecutionState,
+        table::RwTableTag,
+        test_util::assert_code_hashes_consistent,
+        witness::{block_convert, Block, Rw, WitnessError},
+    };
+    use bus_mapping::mock::BlockData;
+    use eth_types::{
+        bytecode, evm_types::OpcodeId, geth_types::GethData, Bytecode, ToBigEndian, Word,
+    };
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use itertools::Itertools;
+    use mock::{
+        eth, generate_mock_call_bytecode, MockCallBytecodeParams, TestContext, MOCK_ACCOUNTS,
+    };
+
+    #[test]
+    fn block_all_keccak_preimages() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let geth_data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code.clone())
+            .unwrap()
+            .into();
+        let mut builder =
+            BlockData::new_from_geth_data(geth_data.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+        let preimages = block.all_keccak_preimages();
+        assert!(preimages.contains(&block.txs[0].rlp_signed));
+        #[cfg(not(feature = "poseidon-codehash"))]
+        assert!(preimages.contains(&code.code()));
+    }
+
+    fn add_block() -> Block<Fr> {
+        let geth_data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        })
+        .unwrap()
+        .into();
+        let mut builder =
+            BlockData::new_from_geth_data(geth_data.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+            .unwrap();
+        block_convert::<Fr>(&builder.block, &builder.code_db).unwrap()
+    }
+
+    #[test]
+    fn block_validate_rw_counters() {
+        let block = add_block();
+        assert_eq!(block.validate_rw_counters(), Ok(()));
+
+        // Give the second stack op the rw counter of the first one.
+        let mut corrupted = block.clone();
+        let stack = corrupted.rws.0.get_mut(&RwTableTag::Stack).unwrap();
+        let first_rw_counter = stack[0].rw_counter();
+        if let Rw::Stack { rw_counter, .. } = &mut stack[1] {
+            *rw_counter = first_rw_counter;
+        }
+        assert_eq!(
+            corrupted.validate_rw_counters(),
+            Err(format!(
+                "Stack rw counter {first_rw_counter} at index 1 is not greater than the previous one {first_rw_counter}"
+            ))
+        );
+
+        // Shift the last stack op, leaving a gap behind.
+        let mut corrupted = block;
+        let stack = corrupted.rws.0.get_mut(&RwTableTag::Stack).unwrap();
+        let last_rw_counter = stack.last().unwrap().rw_counter();
+        if let Some(Rw::Stack { rw_counter, .. }) = stack.last_mut() {
+            *rw_counter += 1000;
+        }
+        assert_eq!(
+            corrupted.validate_rw_counters(),
+            Err(format!("rw counter {last_rw_counter} is missing"))
+        );
+    }
+
+    #[test]
+    fn block_validate_stack_depths() {
+        let block = add_block();
+        assert_eq!(block.validate_stack_depths(), Ok(()));
+
+        // Leave a single item on the stack for ADD to pop two.
+        let mut corrupted = block;
+        let (step_idx, step) = corrupted.txs[0]
+            .steps
+            .iter_mut()
+            .enumerate()
+            .find(|(_, step)| step.execution_state == ExecutionState::ADD_SUB)
+            .unwrap();
+        step.stack_pointer = 1023;
+        assert_eq!(
+            corrupted.validate_stack_depths(),
+            Err(format!(
+                "tx 0 step {step_idx} underflows the stack with ADD at stack pointer 1023"
+            ))
+        );
+    }
+
+    #[test]
+    fn block_check_rw_capacity() {
+        let block = add_block();
+        assert_eq!(block.check_rw_capacity(), Ok(()));
+
+        // the Start rows pushed by EndBlock are padding and not counted
+        let needed = block
+            .rws
+            .0
+            .iter()
+            .filter(|(tag, _)| **tag != RwTableTag::Start)
+            .map(|(_, rws)| rws.len())
+            .sum::<usize>()
+            + 1;
+        let mut fitting = block.clone();
+        fitting.circuits_params.max_rws = needed;
+        assert_eq!(fitting.check_rw_capacity(), Ok(()));
+
+        let mut overflowing = block;
+        overflowing.circuits_params.max_rws = needed - 1;
+        assert_eq!(
+            overflowing.check_rw_capacity(),
+            Err(WitnessError::RwOverflow {
+                needed,
+                max: needed - 1
+            })
+        );
+    }
+
+    #[test]
+    fn block_revert_reason() {
+        assert_eq!(add_block().revert_reason(0), None);
+
+        // ABI encoding of Error("boom").
+        let mut reason = vec![0x08, 0xc3, 0x79, 0xa0];
+        reason.extend(Word::from(0x20).to_be_bytes());
+        reason.extend(Word::from(4).to_be_bytes());
+        reason.extend(b"boom");
+        reason.extend([0; 28]);
+        let mut code = Bytecode::default();
+        for (idx, chunk) in reason.chunks(32).enumerate() {
+            let mut word = [0; 32];
+            word[..chunk.len()].copy_from_slice(chunk);
+            code.push(32, Word::from_big_endian(&word));
+            code.push(1, idx * 32);
+            code.write_op(OpcodeId::MSTORE);
+        }
+        code.push(1, reason.len());
+        code.push(1, 0);
+        code.write_op(OpcodeId::REVERT);
+
+        let geth_data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let mut builder =
+            BlockData::new_from_geth_data(geth_data.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+        assert_eq!(block.revert_reason(0), Some(reason));
+    }
+
+    #[cfg(feature = "debug-export")]
+    #[test]
+    fn block_rws_to_csv() {
+        let block = add_block();
+        let path = std::env::temp_dir().join("block_rws_to_csv.csv");
+        block.rws_to_csv(&path).unwrap();
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("rw_counter,target,is_write,address,key,value,value_prev")
+        );
+        let rows = lines
+            .map(|line| line.split(',').collect_vec())
+            .collect_vec();
+        assert_eq!(
+            rows.len(),
+            block.rws.0.values().map(Vec::len).sum::<usize>()
+        );
+        assert!(rows.iter().all(|row| row.len() == 7));
+    }
+
+    #[test]
+    fn block_assert_rw_eq() {
+        let block = add_block();
+        block.assert_rw_eq(&block.clone());
+    }
+
+    #[test]
+    #[should_panic(expected = "Stack rw at index 2 differs in value")]
+    fn block_assert_rw_eq_diverging_value() {
+        let block = add_block();
+        let mut mutated = block.clone();
+        if let Rw::Stack { value, .. } = &mut mutated.rws.0.get_mut(&RwTableTag::Stack).unwrap()[2]
+        {
+            *value += Word::one();
+        }
+        block.assert_rw_eq(&mutated);
+    }
+
+    #[test]
+    #[should_panic(expected = "Stack rws differ in length")]
+    fn block_assert_rw_eq_missing_rw() {
+        let block = add_block();
+        let mut mutated = block.clone();
+        mutated.rws.0.get_mut(&RwTableTag::Stack).unwrap().pop();
+        block.assert_rw_eq(&mutated);
+    }
+
+    #[test]
+    fn block_code_hashes_consistent() {
+        // Contract A calls contract B, so both bytecodes are in the block.
+        let code_b = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            MSTORE
+            STOP
+        };
+        let code_a = generate_mock_call_bytecode(MockCallBytecodeParams {
+            address: MOCK_ACCOUNTS[1],
+            ..MockCallBytecodeParams::default()
+        });
+        let geth_data: GethData = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(code_a);
+                accs[1].address(MOCK_ACCOUNTS[1]).code(code_b);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let mut builder =
+            BlockData::new_from_geth_data(geth_data.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+        assert!(
+            block
+                .bytecodes
+                .values()
+                .filter(|b| !b.bytes.is_empty())
+                .count()
+                >= 2
+        );
+        assert_code_hashes_consistent(&block);
+    }
+
+    #[test]
+    fn block_touched_storage_slots() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x02)
+            PUSH1(0x05)
+            SSTORE
+            PUSH1(0x03)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let geth_data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let mut builder =
+            BlockData::new_from_geth_data(geth_data.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+        let contract = block.txs[0].calls[0].callee_address;
+        let slots = block.touched_storage_slots();
+        for slot in [Word::zero(), Word::from(5)] {
+            assert_eq!(
+                slots
+                    .iter()
+                    .filter(|touched| **touched == (contract, slot))
+                    .count(),
+                1
+            );
+        }
+        assert!(slots.iter().tuple_windows().all(|(a, b)| a < b));
+    }
+}
@@ -749,6 +749,25 @@ impl Rw {
         }
     }
 
+    #[cfg(feature = "debug-export")]
+    pub(crate) fn value_prev_word(&self) -> Option<U256> {
+        match self {
+            Self::Account { value_prev, .. }
+            | Self::AccountStorage { value_prev, .. }
+            | Self::Memory { value_prev, .. } => Some(*value_prev),
+            Self::TxAccessListAccount { is_warm_prev, .. }
+            | Self::TxAccessListAccountStorage { is_warm_prev, .. } => {
+                Some(U256::from(*is_warm_prev as u64))
+            }
+            Self::TxRefund { value_prev, .. } => Some(U256::from(*value_prev)),
+            Self::Start { .. }
+            | Self::Stack { .. }
+            | Self::CallContext { .. }
+            | Self::TxLog { .. }
+            | Self::TxReceipt { .. } => None,
+        }
+    }
+
     pub(crate) fn value_prev_assignment<F: Field>(&self, randomness: F) -> Option<F> {
         match self {
             Self::Account {
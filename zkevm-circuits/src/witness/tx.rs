@@ -131,6 +131,13 @@ impl Transaction {
         }
     }
 
+    /// Return the gas cost of the call data, 4 per zero byte and 16 per
+    /// non-zero byte. This is the `CallDataGasCost` of the tx table, which
+    /// BeginTx charges as part of the intrinsic gas.
+    pub fn calldata_gas_cost(&self) -> u64 {
+        tx_data_gas_cost(&self.call_data)
+    }
+
     /// Sign data
     pub fn sign_data(&self) -> Result<SignData, Error> {
         if self.r.is_zero() && self.s.is_zero() && self.v == 0 {
@@ -1551,4 +1558,14 @@ mod tests {
             Fr::from(tx_data_gas_cost(&tx.rlp_signed)),
         );
     }
+
+    #[test]
+    fn test_calldata_gas_cost() {
+        let tx = Transaction {
+            call_data: vec![0, 1, 0, 0, 0xff, 0],
+            ..Default::default()
+        };
+        assert_eq!(tx.calldata_gas_cost(), 4 * 4 + 2 * 16);
+        assert_eq!(Transaction::default().calldata_gas_cost(), 0);
+    }
 }